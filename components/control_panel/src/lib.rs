@@ -3,7 +3,5 @@ use bevy::prelude::*;
 pub struct ViewerPlugin;
 
 impl Plugin for ViewerPlugin {
-    fn build(&self, app: &mut App) {
-        app;
-    }
+    fn build(&self, _app: &mut App) {}
 }
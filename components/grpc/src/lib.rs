@@ -5,15 +5,20 @@ use std::net::SocketAddr;
 
 use bevy::prelude::*;
 use serve::spawn_grpc_request_system;
+use viewer_rpc::service::ColorValidation;
 
 #[derive(Debug, Resource)]
 pub struct GrpcServer {
     pub addr: std::net::SocketAddr,
+    pub color_validation: ColorValidation,
 }
 
 impl GrpcServer {
-    pub fn new(addr: SocketAddr) -> Self {
-        Self { addr }
+    pub fn new(addr: SocketAddr, color_validation: ColorValidation) -> Self {
+        Self {
+            addr,
+            color_validation,
+        }
     }
 }
 
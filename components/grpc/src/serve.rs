@@ -6,11 +6,14 @@ use std::thread;
 use tokio::runtime::Runtime;
 use tonic::transport::Server;
 
-use super::viewer_rpc::service::ManageObjectServiceImpl;
+use super::viewer_rpc::service::{ColorValidation, ManageObjectServiceImpl};
 
 /// Starts the gRPC server on the specified socket address.
-pub async fn serve_grpc(addr: std::net::SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
-    let manage_object_service = ManageObjectServiceImpl::default();
+pub async fn serve_grpc(
+    addr: std::net::SocketAddr,
+    color_validation: ColorValidation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manage_object_service = ManageObjectServiceImpl::new(color_validation);
 
     Server::builder()
         .add_service(ManageObjectServiceServer::new(manage_object_service))
@@ -22,11 +25,14 @@ pub async fn serve_grpc(addr: std::net::SocketAddr) -> Result<(), Box<dyn std::e
 /// Spawns a thread running the Tokio runtime to serve the gRPC server.
 pub fn spawn_grpc_request_system(grpc_server: Res<super::GrpcServer>) {
     let addr = grpc_server.addr;
+    let color_validation = grpc_server.color_validation;
 
     thread::spawn(move || {
         let rt = Runtime::new().expect("Failed to create Tokio runtime");
         rt.block_on(async {
-            serve_grpc(addr).await.expect("Failed to start gRPC server");
+            serve_grpc(addr, color_validation)
+                .await
+                .expect("Failed to start gRPC server");
         });
     });
 
@@ -0,0 +1,395 @@
+use std::io::BufReader;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::math::{Mat3, Mat4, Vec3};
+use bevy::render::mesh::{Indices, Mesh, PrimitiveTopology};
+use thiserror::Error;
+
+use protobuf::generated::RawMesh;
+
+#[derive(Error, Debug)]
+pub enum MeshSourceError {
+    #[error("Invalid glTF: {0}")]
+    InvalidGltf(#[from] gltf::Error),
+    #[error("Invalid OBJ: {0}")]
+    InvalidObj(#[from] tobj::LoadError),
+    #[error("The mesh has no triangles")]
+    Empty,
+    #[error("The mesh has {normals} normals for {positions} positions")]
+    NormalCountMismatch { positions: usize, normals: usize },
+    #[error("The number of indices or unindexed positions is not a multiple of three")]
+    IncompleteTriangle,
+    #[error("Index {index} is out of bounds for {positions} positions")]
+    IndexOutOfBounds { index: u32, positions: usize },
+    #[error("The mesh has non-finite positions or normals")]
+    NonFinite,
+}
+
+/// Parses a binary glTF file, or a glTF file with embedded buffers, into a single mesh.
+///
+/// The triangles of every mesh in the default scene are merged, with node transforms applied.
+pub fn gltf_to_mesh(bytes: &[u8]) -> Result<Mesh, MeshSourceError> {
+    let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(bytes)?;
+    // Images are not needed for the geometry, so only the buffers are loaded.
+    let buffers = gltf::import_buffers(&document, None, blob)?;
+
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or(MeshSourceError::Empty)?;
+
+    let mut triangles = TriangleBuffers::default();
+    for node in scene.nodes() {
+        append_gltf_node(&mut triangles, &node, Mat4::IDENTITY, &buffers);
+    }
+
+    triangles.into_mesh()
+}
+
+/// Appends the triangles of a glTF node and its descendants, transformed into scene space.
+fn append_gltf_node(
+    triangles: &mut TriangleBuffers,
+    node: &gltf::Node,
+    parent_transform: Mat4,
+    buffers: &[gltf::buffer::Data],
+) {
+    let transform = parent_transform * Mat4::from_cols_array_2d(&node.transform().matrix());
+    let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh
+            .primitives()
+            .filter(|primitive| primitive.mode() == gltf::mesh::Mode::Triangles)
+        {
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &**data));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+
+            let positions = positions
+                .map(|position| transform.transform_point3(position.into()).to_array())
+                .collect();
+            let normals = reader.read_normals().map(|normals| {
+                normals
+                    .map(|normal| {
+                        (normal_matrix * Vec3::from(normal))
+                            .normalize_or_zero()
+                            .to_array()
+                    })
+                    .collect()
+            });
+            let indices = reader
+                .read_indices()
+                .map(|indices| indices.into_u32().collect());
+
+            triangles.append(positions, normals, indices);
+        }
+    }
+
+    for child in node.children() {
+        append_gltf_node(triangles, &child, transform, buffers);
+    }
+}
+
+/// Parses the contents of a Wavefront OBJ file into a single mesh.
+///
+/// Every object and group is merged. Material libraries are not loaded.
+pub fn obj_to_mesh(obj: &str) -> Result<Mesh, MeshSourceError> {
+    let (models, _) = tobj::load_obj_buf(
+        &mut BufReader::new(obj.as_bytes()),
+        &tobj::LoadOptions {
+            single_index: true,
+            triangulate: true,
+            ..Default::default()
+        },
+        |_| Err(tobj::LoadError::OpenFileFailed),
+    )?;
+
+    let mut triangles = TriangleBuffers::default();
+    for model in models {
+        let mesh = model.mesh;
+        let positions = mesh
+            .positions
+            .chunks_exact(3)
+            .map(|position| [position[0], position[1], position[2]])
+            .collect();
+        let normals = (!mesh.normals.is_empty()).then(|| {
+            mesh.normals
+                .chunks_exact(3)
+                .map(|normal| [normal[0], normal[1], normal[2]])
+                .collect()
+        });
+
+        triangles.append(positions, normals, Some(mesh.indices));
+    }
+
+    triangles.into_mesh()
+}
+
+/// Converts raw vertex buffers into a mesh.
+pub fn raw_mesh_to_mesh(raw_mesh: RawMesh) -> Result<Mesh, MeshSourceError> {
+    let RawMesh {
+        positions,
+        normals,
+        indices,
+    } = raw_mesh;
+
+    let positions = positions
+        .into_iter()
+        .map(|position| [position.x, position.y, position.z])
+        .collect();
+    let normals = (!normals.is_empty()).then(|| {
+        normals
+            .into_iter()
+            .map(|normal| [normal.x, normal.y, normal.z])
+            .collect()
+    });
+    let indices = (!indices.is_empty()).then_some(indices);
+
+    let mut triangles = TriangleBuffers::default();
+    triangles.append(positions, normals, indices);
+    triangles.into_mesh()
+}
+
+/// Vertex buffers of an indexed triangle list, merged from one or more parts.
+#[derive(Default)]
+struct TriangleBuffers {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    indices: Vec<u32>,
+    /// Set when a part came without normals, in which case they are computed for the whole mesh.
+    missing_normals: bool,
+    /// The first error found while appending a part.
+    error: Option<MeshSourceError>,
+}
+
+impl TriangleBuffers {
+    /// Appends a part; without indices, its positions form consecutive triangles.
+    fn append(
+        &mut self,
+        positions: Vec<[f32; 3]>,
+        normals: Option<Vec<[f32; 3]>>,
+        indices: Option<Vec<u32>>,
+    ) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let offset = self.positions.len() as u32;
+        let part_len = positions.len();
+
+        let indices = match indices {
+            Some(indices) => indices,
+            None => (0..part_len as u32).collect(),
+        };
+        if indices.len() % 3 != 0 {
+            self.error = Some(MeshSourceError::IncompleteTriangle);
+            return;
+        }
+        if let Some(&index) = indices.iter().find(|&&index| index as usize >= part_len) {
+            self.error = Some(MeshSourceError::IndexOutOfBounds {
+                index,
+                positions: part_len,
+            });
+            return;
+        }
+
+        match normals {
+            Some(normals) if normals.len() != part_len => {
+                self.error = Some(MeshSourceError::NormalCountMismatch {
+                    positions: part_len,
+                    normals: normals.len(),
+                });
+                return;
+            }
+            Some(normals) => self.normals.extend(normals),
+            None => self.missing_normals = true,
+        }
+
+        self.positions.extend(positions);
+        self.indices
+            .extend(indices.into_iter().map(|index| index + offset));
+    }
+
+    /// Validates the merged buffers and builds the mesh, computing smooth normals if any part lacked them.
+    fn into_mesh(self) -> Result<Mesh, MeshSourceError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.indices.is_empty() {
+            return Err(MeshSourceError::Empty);
+        }
+        let is_finite = |vector: &[f32; 3]| vector.iter().all(|value| value.is_finite());
+        if !self.positions.iter().all(is_finite) || !self.normals.iter().all(is_finite) {
+            return Err(MeshSourceError::NonFinite);
+        }
+
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_indices(Indices::U32(self.indices));
+        if self.missing_normals {
+            mesh.compute_smooth_normals();
+        } else {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        }
+
+        Ok(mesh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::mesh::VertexAttributeValues;
+    use protobuf::generated::Vector3;
+
+    fn raw_vec3(x: f32, y: f32, z: f32) -> Vector3 {
+        Vector3 { x, y, z }
+    }
+
+    fn triangle() -> Vec<[f32; 3]> {
+        vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+    }
+
+    #[test]
+    fn triangle_buffers_offset_the_indices_of_later_parts() {
+        let mut triangles = TriangleBuffers::default();
+        triangles.append(triangle(), None, Some(vec![0, 1, 2]));
+        triangles.append(triangle(), None, Some(vec![2, 1, 0]));
+
+        let mesh = triangles.into_mesh().unwrap();
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        assert_eq!(indices, vec![0, 1, 2, 5, 4, 3]);
+        assert_eq!(mesh.count_vertices(), 6);
+    }
+
+    #[test]
+    fn triangle_buffers_compute_normals_if_a_part_lacks_them() {
+        let mut triangles = TriangleBuffers::default();
+        triangles.append(triangle(), Some(vec![[0.0, 0.0, -1.0]; 3]), None);
+        triangles.append(triangle(), None, None);
+
+        let mesh = triangles.into_mesh().unwrap();
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("the mesh has no normals");
+        };
+        assert_eq!(normals.len(), 6);
+        assert!(normals.iter().all(|normal| *normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn triangle_buffers_keep_the_first_error() {
+        let mut triangles = TriangleBuffers::default();
+        triangles.append(triangle(), None, Some(vec![0, 1]));
+        triangles.append(triangle(), None, Some(vec![0, 1, 3]));
+
+        assert!(matches!(
+            triangles.into_mesh(),
+            Err(MeshSourceError::IncompleteTriangle)
+        ));
+    }
+
+    #[test]
+    fn triangle_buffers_reject_empty_and_non_finite_meshes() {
+        assert!(matches!(
+            TriangleBuffers::default().into_mesh(),
+            Err(MeshSourceError::Empty)
+        ));
+
+        let mut triangles = TriangleBuffers::default();
+        triangles.append(
+            vec![[0.0, 0.0, 0.0], [f32::NAN, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            None,
+            None,
+        );
+        assert!(matches!(
+            triangles.into_mesh(),
+            Err(MeshSourceError::NonFinite)
+        ));
+    }
+
+    #[test]
+    fn raw_mesh_indices_must_be_in_bounds() {
+        let raw_mesh = |indices| RawMesh {
+            positions: vec![
+                raw_vec3(0.0, 0.0, 0.0),
+                raw_vec3(1.0, 0.0, 0.0),
+                raw_vec3(0.0, 1.0, 0.0),
+            ],
+            normals: vec![],
+            indices,
+        };
+
+        assert!(raw_mesh_to_mesh(raw_mesh(vec![0, 1, 2])).is_ok());
+        assert!(matches!(
+            raw_mesh_to_mesh(raw_mesh(vec![0, 1, 3])),
+            Err(MeshSourceError::IndexOutOfBounds {
+                index: 3,
+                positions: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn raw_mesh_needs_a_normal_per_position() {
+        let raw_mesh = RawMesh {
+            positions: vec![
+                raw_vec3(0.0, 0.0, 0.0),
+                raw_vec3(1.0, 0.0, 0.0),
+                raw_vec3(0.0, 1.0, 0.0),
+            ],
+            normals: vec![raw_vec3(0.0, 0.0, 1.0)],
+            indices: vec![],
+        };
+
+        assert!(matches!(
+            raw_mesh_to_mesh(raw_mesh),
+            Err(MeshSourceError::NormalCountMismatch {
+                positions: 3,
+                normals: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn obj_faces_are_triangulated_and_merged() {
+        let obj = "\
+o first
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3 4
+o second
+v 0 0 1
+v 1 0 1
+v 0 1 1
+f 5 6 7
+";
+
+        let mesh = obj_to_mesh(obj).unwrap();
+        assert_eq!(mesh.indices().unwrap().len(), 9);
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some());
+    }
+
+    #[test]
+    fn obj_without_faces_is_empty() {
+        assert!(matches!(
+            obj_to_mesh("v 0 0 0\nv 1 0 0\nv 0 1 0\n"),
+            Err(MeshSourceError::Empty)
+        ));
+    }
+
+    #[test]
+    fn invalid_gltf_is_rejected() {
+        assert!(matches!(
+            gltf_to_mesh(b"not a gltf file"),
+            Err(MeshSourceError::InvalidGltf(_))
+        ));
+    }
+}
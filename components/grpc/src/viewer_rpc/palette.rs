@@ -0,0 +1,448 @@
+use bevy::color::Srgba;
+use bevy::color::palettes::{css, tailwind};
+
+/// Looks up a color of the CSS or Tailwind palette by name.
+///
+/// CSS colors go by their plain name, such as `rebeccapurple`, and Tailwind colors by hue and
+/// shade, such as `sky-500`. Case, spaces, hyphens and underscores are ignored.
+pub fn named_color(name: &str) -> Option<Srgba> {
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    let color = match name.as_str() {
+        "aliceblue" => css::ALICE_BLUE,
+        "antiquewhite" => css::ANTIQUE_WHITE,
+        "aqua" => css::AQUA,
+        "aquamarine" => css::AQUAMARINE,
+        "azure" => css::AZURE,
+        "beige" => css::BEIGE,
+        "bisque" => css::BISQUE,
+        "black" => css::BLACK,
+        "blanchedalmond" => css::BLANCHED_ALMOND,
+        "blue" => css::BLUE,
+        "blueviolet" => css::BLUE_VIOLET,
+        "brown" => css::BROWN,
+        "burlywood" => css::BURLYWOOD,
+        "cadetblue" => css::CADET_BLUE,
+        "chartreuse" => css::CHARTREUSE,
+        "chocolate" => css::CHOCOLATE,
+        "coral" => css::CORAL,
+        "cornflowerblue" => css::CORNFLOWER_BLUE,
+        "cornsilk" => css::CORNSILK,
+        "crimson" => css::CRIMSON,
+        "darkblue" => css::DARK_BLUE,
+        "darkcyan" => css::DARK_CYAN,
+        "darkgoldenrod" => css::DARK_GOLDENROD,
+        "darkgray" => css::DARK_GRAY,
+        "darkgreen" => css::DARK_GREEN,
+        "darkgrey" => css::DARK_GREY,
+        "darkkhaki" => css::DARK_KHAKI,
+        "darkmagenta" => css::DARK_MAGENTA,
+        "darkolivegreen" => css::DARK_OLIVEGREEN,
+        "darkorange" => css::DARK_ORANGE,
+        "darkorchid" => css::DARK_ORCHID,
+        "darkred" => css::DARK_RED,
+        "darksalmon" => css::DARK_SALMON,
+        "darkseagreen" => css::DARK_SEA_GREEN,
+        "darkslateblue" => css::DARK_SLATE_BLUE,
+        "darkslategray" => css::DARK_SLATE_GRAY,
+        "darkslategrey" => css::DARK_SLATE_GREY,
+        "darkturquoise" => css::DARK_TURQUOISE,
+        "darkviolet" => css::DARK_VIOLET,
+        "deeppink" => css::DEEP_PINK,
+        "deepskyblue" => css::DEEP_SKY_BLUE,
+        "dimgray" => css::DIM_GRAY,
+        "dimgrey" => css::DIM_GREY,
+        "dodgerblue" => css::DODGER_BLUE,
+        "firebrick" => css::FIRE_BRICK,
+        "floralwhite" => css::FLORAL_WHITE,
+        "forestgreen" => css::FOREST_GREEN,
+        "fuchsia" => css::FUCHSIA,
+        "gainsboro" => css::GAINSBORO,
+        "ghostwhite" => css::GHOST_WHITE,
+        "gold" => css::GOLD,
+        "goldenrod" => css::GOLDENROD,
+        "gray" => css::GRAY,
+        "green" => css::GREEN,
+        "greenyellow" => css::GREEN_YELLOW,
+        "grey" => css::GREY,
+        "honeydew" => css::HONEYDEW,
+        "hotpink" => css::HOT_PINK,
+        "indianred" => css::INDIAN_RED,
+        "indigo" => css::INDIGO,
+        "ivory" => css::IVORY,
+        "khaki" => css::KHAKI,
+        "lavender" => css::LAVENDER,
+        "lavenderblush" => css::LAVENDER_BLUSH,
+        "lawngreen" => css::LAWN_GREEN,
+        "lemonchiffon" => css::LEMON_CHIFFON,
+        "lightblue" => css::LIGHT_BLUE,
+        "lightcoral" => css::LIGHT_CORAL,
+        "lightcyan" => css::LIGHT_CYAN,
+        "lightgoldenrodyellow" => css::LIGHT_GOLDENROD_YELLOW,
+        "lightgray" => css::LIGHT_GRAY,
+        "lightgreen" => css::LIGHT_GREEN,
+        "lightgrey" => css::LIGHT_GREY,
+        "lightpink" => css::LIGHT_PINK,
+        "lightsalmon" => css::LIGHT_SALMON,
+        "lightseagreen" => css::LIGHT_SEA_GREEN,
+        "lightskyblue" => css::LIGHT_SKY_BLUE,
+        "lightslategray" => css::LIGHT_SLATE_GRAY,
+        "lightslategrey" => css::LIGHT_SLATE_GREY,
+        "lightsteelblue" => css::LIGHT_STEEL_BLUE,
+        "lightyellow" => css::LIGHT_YELLOW,
+        "lime" => css::LIME,
+        "limegreen" => css::LIMEGREEN,
+        "linen" => css::LINEN,
+        "magenta" => css::MAGENTA,
+        "maroon" => css::MAROON,
+        "mediumaquamarine" => css::MEDIUM_AQUAMARINE,
+        "mediumblue" => css::MEDIUM_BLUE,
+        "mediumorchid" => css::MEDIUM_ORCHID,
+        "mediumpurple" => css::MEDIUM_PURPLE,
+        "mediumseagreen" => css::MEDIUM_SEA_GREEN,
+        "mediumslateblue" => css::MEDIUM_SLATE_BLUE,
+        "mediumspringgreen" => css::MEDIUM_SPRING_GREEN,
+        "mediumturquoise" => css::MEDIUM_TURQUOISE,
+        "mediumvioletred" => css::MEDIUM_VIOLET_RED,
+        "midnightblue" => css::MIDNIGHT_BLUE,
+        "mintcream" => css::MINT_CREAM,
+        "mistyrose" => css::MISTY_ROSE,
+        "moccasin" => css::MOCCASIN,
+        "navajowhite" => css::NAVAJO_WHITE,
+        "navy" => css::NAVY,
+        "oldlace" => css::OLD_LACE,
+        "olive" => css::OLIVE,
+        "olivedrab" => css::OLIVE_DRAB,
+        "orange" => css::ORANGE,
+        "orangered" => css::ORANGE_RED,
+        "orchid" => css::ORCHID,
+        "palegoldenrod" => css::PALE_GOLDENROD,
+        "palegreen" => css::PALE_GREEN,
+        "paleturquoise" => css::PALE_TURQUOISE,
+        "palevioletred" => css::PALE_VIOLETRED,
+        "papayawhip" => css::PAPAYA_WHIP,
+        "peachpuff" => css::PEACHPUFF,
+        "peru" => css::PERU,
+        "pink" => css::PINK,
+        "plum" => css::PLUM,
+        "powderblue" => css::POWDER_BLUE,
+        "purple" => css::PURPLE,
+        "rebeccapurple" => css::REBECCA_PURPLE,
+        "red" => css::RED,
+        "rosybrown" => css::ROSY_BROWN,
+        "royalblue" => css::ROYAL_BLUE,
+        "saddlebrown" => css::SADDLE_BROWN,
+        "salmon" => css::SALMON,
+        "sandybrown" => css::SANDY_BROWN,
+        "seashell" => css::SEASHELL,
+        "seagreen" => css::SEA_GREEN,
+        "sienna" => css::SIENNA,
+        "silver" => css::SILVER,
+        "skyblue" => css::SKY_BLUE,
+        "slateblue" => css::SLATE_BLUE,
+        "slategray" => css::SLATE_GRAY,
+        "slategrey" => css::SLATE_GREY,
+        "snow" => css::SNOW,
+        "springgreen" => css::SPRING_GREEN,
+        "steelblue" => css::STEEL_BLUE,
+        "tan" => css::TAN,
+        "teal" => css::TEAL,
+        "thistle" => css::THISTLE,
+        "tomato" => css::TOMATO,
+        "turquoise" => css::TURQUOISE,
+        "violet" => css::VIOLET,
+        "wheat" => css::WHEAT,
+        "white" => css::WHITE,
+        "whitesmoke" => css::WHITE_SMOKE,
+        "yellow" => css::YELLOW,
+        "yellowgreen" => css::YELLOW_GREEN,
+        "amber50" => tailwind::AMBER_50,
+        "amber100" => tailwind::AMBER_100,
+        "amber200" => tailwind::AMBER_200,
+        "amber300" => tailwind::AMBER_300,
+        "amber400" => tailwind::AMBER_400,
+        "amber500" => tailwind::AMBER_500,
+        "amber600" => tailwind::AMBER_600,
+        "amber700" => tailwind::AMBER_700,
+        "amber800" => tailwind::AMBER_800,
+        "amber900" => tailwind::AMBER_900,
+        "amber950" => tailwind::AMBER_950,
+        "blue50" => tailwind::BLUE_50,
+        "blue100" => tailwind::BLUE_100,
+        "blue200" => tailwind::BLUE_200,
+        "blue300" => tailwind::BLUE_300,
+        "blue400" => tailwind::BLUE_400,
+        "blue500" => tailwind::BLUE_500,
+        "blue600" => tailwind::BLUE_600,
+        "blue700" => tailwind::BLUE_700,
+        "blue800" => tailwind::BLUE_800,
+        "blue900" => tailwind::BLUE_900,
+        "blue950" => tailwind::BLUE_950,
+        "cyan50" => tailwind::CYAN_50,
+        "cyan100" => tailwind::CYAN_100,
+        "cyan200" => tailwind::CYAN_200,
+        "cyan300" => tailwind::CYAN_300,
+        "cyan400" => tailwind::CYAN_400,
+        "cyan500" => tailwind::CYAN_500,
+        "cyan600" => tailwind::CYAN_600,
+        "cyan700" => tailwind::CYAN_700,
+        "cyan800" => tailwind::CYAN_800,
+        "cyan900" => tailwind::CYAN_900,
+        "cyan950" => tailwind::CYAN_950,
+        "emerald50" => tailwind::EMERALD_50,
+        "emerald100" => tailwind::EMERALD_100,
+        "emerald200" => tailwind::EMERALD_200,
+        "emerald300" => tailwind::EMERALD_300,
+        "emerald400" => tailwind::EMERALD_400,
+        "emerald500" => tailwind::EMERALD_500,
+        "emerald600" => tailwind::EMERALD_600,
+        "emerald700" => tailwind::EMERALD_700,
+        "emerald800" => tailwind::EMERALD_800,
+        "emerald900" => tailwind::EMERALD_900,
+        "emerald950" => tailwind::EMERALD_950,
+        "fuchsia50" => tailwind::FUCHSIA_50,
+        "fuchsia100" => tailwind::FUCHSIA_100,
+        "fuchsia200" => tailwind::FUCHSIA_200,
+        "fuchsia300" => tailwind::FUCHSIA_300,
+        "fuchsia400" => tailwind::FUCHSIA_400,
+        "fuchsia500" => tailwind::FUCHSIA_500,
+        "fuchsia600" => tailwind::FUCHSIA_600,
+        "fuchsia700" => tailwind::FUCHSIA_700,
+        "fuchsia800" => tailwind::FUCHSIA_800,
+        "fuchsia900" => tailwind::FUCHSIA_900,
+        "fuchsia950" => tailwind::FUCHSIA_950,
+        "gray50" => tailwind::GRAY_50,
+        "gray100" => tailwind::GRAY_100,
+        "gray200" => tailwind::GRAY_200,
+        "gray300" => tailwind::GRAY_300,
+        "gray400" => tailwind::GRAY_400,
+        "gray500" => tailwind::GRAY_500,
+        "gray600" => tailwind::GRAY_600,
+        "gray700" => tailwind::GRAY_700,
+        "gray800" => tailwind::GRAY_800,
+        "gray900" => tailwind::GRAY_900,
+        "gray950" => tailwind::GRAY_950,
+        "green50" => tailwind::GREEN_50,
+        "green100" => tailwind::GREEN_100,
+        "green200" => tailwind::GREEN_200,
+        "green300" => tailwind::GREEN_300,
+        "green400" => tailwind::GREEN_400,
+        "green500" => tailwind::GREEN_500,
+        "green600" => tailwind::GREEN_600,
+        "green700" => tailwind::GREEN_700,
+        "green800" => tailwind::GREEN_800,
+        "green900" => tailwind::GREEN_900,
+        "green950" => tailwind::GREEN_950,
+        "indigo50" => tailwind::INDIGO_50,
+        "indigo100" => tailwind::INDIGO_100,
+        "indigo200" => tailwind::INDIGO_200,
+        "indigo300" => tailwind::INDIGO_300,
+        "indigo400" => tailwind::INDIGO_400,
+        "indigo500" => tailwind::INDIGO_500,
+        "indigo600" => tailwind::INDIGO_600,
+        "indigo700" => tailwind::INDIGO_700,
+        "indigo800" => tailwind::INDIGO_800,
+        "indigo900" => tailwind::INDIGO_900,
+        "indigo950" => tailwind::INDIGO_950,
+        "lime50" => tailwind::LIME_50,
+        "lime100" => tailwind::LIME_100,
+        "lime200" => tailwind::LIME_200,
+        "lime300" => tailwind::LIME_300,
+        "lime400" => tailwind::LIME_400,
+        "lime500" => tailwind::LIME_500,
+        "lime600" => tailwind::LIME_600,
+        "lime700" => tailwind::LIME_700,
+        "lime800" => tailwind::LIME_800,
+        "lime900" => tailwind::LIME_900,
+        "lime950" => tailwind::LIME_950,
+        "neutral50" => tailwind::NEUTRAL_50,
+        "neutral100" => tailwind::NEUTRAL_100,
+        "neutral200" => tailwind::NEUTRAL_200,
+        "neutral300" => tailwind::NEUTRAL_300,
+        "neutral400" => tailwind::NEUTRAL_400,
+        "neutral500" => tailwind::NEUTRAL_500,
+        "neutral600" => tailwind::NEUTRAL_600,
+        "neutral700" => tailwind::NEUTRAL_700,
+        "neutral800" => tailwind::NEUTRAL_800,
+        "neutral900" => tailwind::NEUTRAL_900,
+        "neutral950" => tailwind::NEUTRAL_950,
+        "orange50" => tailwind::ORANGE_50,
+        "orange100" => tailwind::ORANGE_100,
+        "orange200" => tailwind::ORANGE_200,
+        "orange300" => tailwind::ORANGE_300,
+        "orange400" => tailwind::ORANGE_400,
+        "orange500" => tailwind::ORANGE_500,
+        "orange600" => tailwind::ORANGE_600,
+        "orange700" => tailwind::ORANGE_700,
+        "orange800" => tailwind::ORANGE_800,
+        "orange900" => tailwind::ORANGE_900,
+        "orange950" => tailwind::ORANGE_950,
+        "pink50" => tailwind::PINK_50,
+        "pink100" => tailwind::PINK_100,
+        "pink200" => tailwind::PINK_200,
+        "pink300" => tailwind::PINK_300,
+        "pink400" => tailwind::PINK_400,
+        "pink500" => tailwind::PINK_500,
+        "pink600" => tailwind::PINK_600,
+        "pink700" => tailwind::PINK_700,
+        "pink800" => tailwind::PINK_800,
+        "pink900" => tailwind::PINK_900,
+        "pink950" => tailwind::PINK_950,
+        "purple50" => tailwind::PURPLE_50,
+        "purple100" => tailwind::PURPLE_100,
+        "purple200" => tailwind::PURPLE_200,
+        "purple300" => tailwind::PURPLE_300,
+        "purple400" => tailwind::PURPLE_400,
+        "purple500" => tailwind::PURPLE_500,
+        "purple600" => tailwind::PURPLE_600,
+        "purple700" => tailwind::PURPLE_700,
+        "purple800" => tailwind::PURPLE_800,
+        "purple900" => tailwind::PURPLE_900,
+        "purple950" => tailwind::PURPLE_950,
+        "red50" => tailwind::RED_50,
+        "red100" => tailwind::RED_100,
+        "red200" => tailwind::RED_200,
+        "red300" => tailwind::RED_300,
+        "red400" => tailwind::RED_400,
+        "red500" => tailwind::RED_500,
+        "red600" => tailwind::RED_600,
+        "red700" => tailwind::RED_700,
+        "red800" => tailwind::RED_800,
+        "red900" => tailwind::RED_900,
+        "red950" => tailwind::RED_950,
+        "rose50" => tailwind::ROSE_50,
+        "rose100" => tailwind::ROSE_100,
+        "rose200" => tailwind::ROSE_200,
+        "rose300" => tailwind::ROSE_300,
+        "rose400" => tailwind::ROSE_400,
+        "rose500" => tailwind::ROSE_500,
+        "rose600" => tailwind::ROSE_600,
+        "rose700" => tailwind::ROSE_700,
+        "rose800" => tailwind::ROSE_800,
+        "rose900" => tailwind::ROSE_900,
+        "rose950" => tailwind::ROSE_950,
+        "sky50" => tailwind::SKY_50,
+        "sky100" => tailwind::SKY_100,
+        "sky200" => tailwind::SKY_200,
+        "sky300" => tailwind::SKY_300,
+        "sky400" => tailwind::SKY_400,
+        "sky500" => tailwind::SKY_500,
+        "sky600" => tailwind::SKY_600,
+        "sky700" => tailwind::SKY_700,
+        "sky800" => tailwind::SKY_800,
+        "sky900" => tailwind::SKY_900,
+        "sky950" => tailwind::SKY_950,
+        "slate50" => tailwind::SLATE_50,
+        "slate100" => tailwind::SLATE_100,
+        "slate200" => tailwind::SLATE_200,
+        "slate300" => tailwind::SLATE_300,
+        "slate400" => tailwind::SLATE_400,
+        "slate500" => tailwind::SLATE_500,
+        "slate600" => tailwind::SLATE_600,
+        "slate700" => tailwind::SLATE_700,
+        "slate800" => tailwind::SLATE_800,
+        "slate900" => tailwind::SLATE_900,
+        "slate950" => tailwind::SLATE_950,
+        "stone50" => tailwind::STONE_50,
+        "stone100" => tailwind::STONE_100,
+        "stone200" => tailwind::STONE_200,
+        "stone300" => tailwind::STONE_300,
+        "stone400" => tailwind::STONE_400,
+        "stone500" => tailwind::STONE_500,
+        "stone600" => tailwind::STONE_600,
+        "stone700" => tailwind::STONE_700,
+        "stone800" => tailwind::STONE_800,
+        "stone900" => tailwind::STONE_900,
+        "stone950" => tailwind::STONE_950,
+        "teal50" => tailwind::TEAL_50,
+        "teal100" => tailwind::TEAL_100,
+        "teal200" => tailwind::TEAL_200,
+        "teal300" => tailwind::TEAL_300,
+        "teal400" => tailwind::TEAL_400,
+        "teal500" => tailwind::TEAL_500,
+        "teal600" => tailwind::TEAL_600,
+        "teal700" => tailwind::TEAL_700,
+        "teal800" => tailwind::TEAL_800,
+        "teal900" => tailwind::TEAL_900,
+        "teal950" => tailwind::TEAL_950,
+        "violet50" => tailwind::VIOLET_50,
+        "violet100" => tailwind::VIOLET_100,
+        "violet200" => tailwind::VIOLET_200,
+        "violet300" => tailwind::VIOLET_300,
+        "violet400" => tailwind::VIOLET_400,
+        "violet500" => tailwind::VIOLET_500,
+        "violet600" => tailwind::VIOLET_600,
+        "violet700" => tailwind::VIOLET_700,
+        "violet800" => tailwind::VIOLET_800,
+        "violet900" => tailwind::VIOLET_900,
+        "violet950" => tailwind::VIOLET_950,
+        "yellow50" => tailwind::YELLOW_50,
+        "yellow100" => tailwind::YELLOW_100,
+        "yellow200" => tailwind::YELLOW_200,
+        "yellow300" => tailwind::YELLOW_300,
+        "yellow400" => tailwind::YELLOW_400,
+        "yellow500" => tailwind::YELLOW_500,
+        "yellow600" => tailwind::YELLOW_600,
+        "yellow700" => tailwind::YELLOW_700,
+        "yellow800" => tailwind::YELLOW_800,
+        "yellow900" => tailwind::YELLOW_900,
+        "yellow950" => tailwind::YELLOW_950,
+        "zinc50" => tailwind::ZINC_50,
+        "zinc100" => tailwind::ZINC_100,
+        "zinc200" => tailwind::ZINC_200,
+        "zinc300" => tailwind::ZINC_300,
+        "zinc400" => tailwind::ZINC_400,
+        "zinc500" => tailwind::ZINC_500,
+        "zinc600" => tailwind::ZINC_600,
+        "zinc700" => tailwind::ZINC_700,
+        "zinc800" => tailwind::ZINC_800,
+        "zinc900" => tailwind::ZINC_900,
+        "zinc950" => tailwind::ZINC_950,
+        _ => return None,
+    };
+
+    Some(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn css_names_are_found() {
+        assert_eq!(named_color("rebeccapurple"), Some(css::REBECCA_PURPLE));
+        assert_eq!(named_color("black"), Some(css::BLACK));
+    }
+
+    #[test]
+    fn tailwind_names_are_found() {
+        assert_eq!(named_color("sky-500"), Some(tailwind::SKY_500));
+        assert_eq!(named_color("zinc950"), Some(tailwind::ZINC_950));
+    }
+
+    #[test]
+    fn case_spaces_hyphens_and_underscores_are_ignored() {
+        for name in [
+            "RebeccaPurple",
+            "rebecca purple",
+            "rebecca-purple",
+            "REBECCA_PURPLE",
+        ] {
+            assert_eq!(named_color(name), Some(css::REBECCA_PURPLE), "{name:?}");
+        }
+        assert_eq!(named_color("Sky 500"), Some(tailwind::SKY_500));
+        assert_eq!(named_color("SKY_500"), Some(tailwind::SKY_500));
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        assert_eq!(named_color(""), None);
+        assert_eq!(named_color("sky"), None);
+        assert_eq!(named_color("sky-550"), None);
+        assert_eq!(named_color("rebecca.purple"), None);
+    }
+}
@@ -1,57 +1,138 @@
+use std::collections::HashSet;
+use std::pin::Pin;
+
 use thiserror::Error;
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::{Stream, StreamExt};
 
-use viewer::manage_objects::global::INTERNAL_REQUEST_LIST;
+use viewer::manage_objects::global::{
+    INTERNAL_REQUEST_QUEUE, InternalRequestQueue, OBJECT_CHANGE_BROADCAST, ObjectChangeNotification,
+};
+use viewer::manage_objects::request::annotation::AnnotationRequest;
+use viewer::manage_objects::request::motion::MotionRequest;
+use viewer::manage_objects::request::object::{Completion, ObjectChange, ObjectRequestError};
+use viewer::manage_objects::request::primitive::PrimitiveRequest;
+use viewer::manage_objects::request::query::{ObjectPage, ObjectSnapshot, QueryRequest};
+use viewer::manage_objects::request::trail::TrailRequest;
 use viewer::manage_objects::request::{self, InternalRequest, object::ObjectRequest};
+use viewer::types::ResponseSender;
 
 use protobuf::generated::manage_object_service_server::ManageObjectService;
 use protobuf::generated::{
-    ObjectColor, ObjectColorEnum, ObjectId, ObjectShape, ObjectSize, SetObjectPositionRequest,
+    AnimationRepeat, AnnotationId, AnnotationText, ArrowPrimitive, BatchOperation, BatchRequest,
+    BatchResponse, CapsuleDimensions, ClearPrimitivesRequest, ClearPrimitivesResponse,
+    ConeDimensions, CuboidDimensions, CylinderDimensions, DespawnObjectRequest,
+    DespawnObjectResponse, DespawnObjectSequenceRequest, DespawnObjectSequenceResponse,
+    EasingFunction, ExponentialDecayInterpolation, ExtrapolateInterpolation, GetObjectRequest,
+    GetObjectResponse, GetSceneSnapshotRequest, GetSceneSnapshotResponse, Hsla, Hsva,
+    InterpolationMode, Keyframe, LerpInterpolation, LinePrimitive, LinearInterpolation,
+    ListObjectsRequest, ListObjectsResponse, MeshId, ObjectAlphaMode, ObjectAnchor,
+    ObjectAnimation, ObjectColor, ObjectColorEnum, ObjectDespawned, ObjectDimensions, ObjectEvent,
+    ObjectId, ObjectMaterial, ObjectMoved, ObjectParentChanged, ObjectProperties,
+    ObjectPropertiesChanged, ObjectShape, ObjectSize, ObjectSpawned, ObjectState, ObjectTrail,
+    ObjectUpdateAck, ObjectUpdateBatch, ObjectUpdateFailure, PlaneDimensions, PolylinePrimitive,
+    Primitive, PrimitiveId, PrimitivePoint, Quaternion, RegisterMeshRequest, RegisterMeshResponse,
+    RemoveAnnotationRequest, RemoveAnnotationResponse, RemovePrimitiveRequest,
+    RemovePrimitiveResponse, Rgba, SetAnnotationRequest, SetAnnotationResponse,
+    SetInterpolationRequest, SetInterpolationResponse, SetObjectAnimationRequest,
+    SetObjectAnimationResponse, SetObjectLabelRequest, SetObjectLabelResponse,
+    SetObjectParentRequest, SetObjectParentResponse, SetObjectPositionRequest,
     SetObjectPositionResponse, SetObjectPositionSequenceRequest, SetObjectPositionSequenceResponse,
-    SpawnObjectRequest, SpawnObjectResponse, SpawnObjectSequenceRequest,
-    SpawnObjectSequenceResponse, Uuid, object_color,
+    SetObjectPropertiesRequest, SetObjectPropertiesResponse, SetObjectPropertiesSequenceRequest,
+    SetObjectPropertiesSequenceResponse, SetObjectRotationRequest, SetObjectRotationResponse,
+    SetObjectScaleRequest, SetObjectScaleResponse, SetObjectTrailRequest, SetObjectTrailResponse,
+    SetObjectTransformRequest, SetObjectTransformResponse, SetObjectTransformSequenceRequest,
+    SetObjectTransformSequenceResponse, SetPrimitiveRequest, SetPrimitiveResponse,
+    SnapInterpolation, SpawnObjectRequest, SpawnObjectResponse, SpawnObjectSequenceRequest,
+    SpawnObjectSequenceResponse, SphereDimensions, TorusDimensions, Transform, TweenInterpolation,
+    Uuid, Vector2, Vector3, WatchObjectsRequest, batch_operation, interpolation_mode, object_color,
+    object_dimensions, object_event, primitive, primitive_point, register_mesh_request,
 };
 
 use bevy::log::{trace, trace_span, warn};
 
-use bevy::math::Vec3;
+use super::mesh::{self, MeshSourceError};
+use super::palette;
+
+use bevy::math::curve::EaseFunction;
+use bevy::math::{Quat, Vec2, Vec3};
 use tonic::Response;
 
 #[derive(Default)]
-pub struct ManageObjectServiceImpl {}
+pub struct ManageObjectServiceImpl {
+    color_validation: ColorValidation,
+}
+
+impl ManageObjectServiceImpl {
+    /// Creates the service with the given treatment of unspecified colors.
+    pub fn new(color_validation: ColorValidation) -> Self {
+        Self { color_validation }
+    }
+}
+
+/// How an unspecified color enum value in a request is treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorValidation {
+    /// Unspecified colors become white, with a warning.
+    #[default]
+    Lenient,
+    /// Unspecified colors are rejected as invalid.
+    Strict,
+}
 
 #[tonic::async_trait]
 impl ManageObjectService for ManageObjectServiceImpl {
+    #[doc = " Server streaming response type for the WatchObjects method."]
+    type WatchObjectsStream =
+        Pin<Box<dyn Stream<Item = std::result::Result<ObjectEvent, tonic::Status>> + Send>>;
+
+    #[doc = " Server streaming response type for the StreamObjectUpdates method."]
+    type StreamObjectUpdatesStream =
+        Pin<Box<dyn Stream<Item = std::result::Result<ObjectUpdateAck, tonic::Status>> + Send>>;
+
     #[doc = " Sets the position of the object."]
     async fn set_object_position(
         &self,
         request: tonic::Request<SetObjectPositionRequest>,
     ) -> std::result::Result<tonic::Response<SetObjectPositionResponse>, tonic::Status> {
-        // Create a span for tracing
-        let _span = trace_span!("set_object_position_rpc").entered();
+        let (completion, receiver) = Completion::channel();
 
-        let request = request.into_inner();
+        {
+            // Create a span for tracing
+            let _span = trace_span!("set_object_position_rpc").entered();
 
-        let internal_request = match set_position_request_to_internal_request(request) {
-            Ok(object) => object,
-            Err(e) => {
-                return match e {
-                    SetObjectPositionError::InvalidObjectId => {
-                        Err(tonic::Status::invalid_argument(e.to_string()))
-                    }
-                    SetObjectPositionError::InvalidPosition => {
-                        Err(tonic::Status::invalid_argument(e.to_string()))
+            let request = request.into_inner();
+
+            let internal_request =
+                match set_position_request_to_internal_request(request, Some(completion)) {
+                    Ok(object) => object,
+                    Err(e) => {
+                        return match e {
+                            SetObjectPositionError::InvalidObjectId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                            SetObjectPositionError::InvalidPosition => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                            SetObjectPositionError::InvalidTimestamp => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                        };
                     }
                 };
-            }
-        };
 
-        trace!("Internal request: {:?}", &internal_request);
+            trace!("Internal request: {:?}", &internal_request);
 
-        INTERNAL_REQUEST_LIST.push(InternalRequest::ObjectRequest(ObjectRequest::SetPosition(
-            internal_request,
-        )));
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::SetPosition(
+                internal_request,
+            )))?;
+
+            trace!("Set position request added to queue");
+        }
 
-        trace!("Set position request added to queue");
+        await_completion(receiver).await?;
 
         Ok(Response::new(SetObjectPositionResponse { success: true }))
     }
@@ -61,45 +142,68 @@ impl ManageObjectService for ManageObjectServiceImpl {
         &self,
         request: tonic::Request<SpawnObjectRequest>,
     ) -> std::result::Result<tonic::Response<SpawnObjectResponse>, tonic::Status> {
-        let _span = trace_span!("spawn_object_rpc").entered();
+        let (completion, receiver) = Completion::channel();
 
-        let request = request.into_inner();
+        let response = {
+            let _span = trace_span!("spawn_object_rpc").entered();
 
-        let internal_request = match spawn_object_request_to_internal_request(request) {
-            Ok(object) => object,
-            Err(e) => {
-                return match e {
-                    SpawnObjectError::InvalidObjectColor => {
-                        Err(tonic::Status::invalid_argument(e.to_string()))
-                    }
-                    SpawnObjectError::InvalidObjectShape => {
-                        Err(tonic::Status::invalid_argument(e.to_string()))
-                    }
-                    SpawnObjectError::InvalidPosition => {
-                        Err(tonic::Status::invalid_argument(e.to_string()))
-                    }
-                    SpawnObjectError::InvalidObjectProperties => {
-                        Err(tonic::Status::invalid_argument(e.to_string()))
-                    }
-                };
-            }
-        };
+            let request = request.into_inner();
 
-        let response = Response::new(SpawnObjectResponse {
-            spawend_object_id: Some(ObjectId {
-                uuid: Some(Uuid {
-                    value: (&internal_request).object_id.uuid.as_bytes().to_vec(),
-                }),
-            }),
-        });
+            let internal_request = match spawn_object_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        SpawnObjectError::InvalidObjectId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidObjectColor => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidObjectShape => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidPosition => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidObjectProperties => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidObjectDimensions => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidObjectSize => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidObjectMaterial => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SpawnObjectError::InvalidParentId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
+                }
+            };
 
-        trace!("Internal request: {:?}", &internal_request);
+            let response = Response::new(SpawnObjectResponse {
+                spawend_object_id: Some(object_id_to_proto(&internal_request.object_id)),
+            });
 
-        INTERNAL_REQUEST_LIST.push(InternalRequest::ObjectRequest(ObjectRequest::Spawn(
-            internal_request,
-        )));
+            trace!("Internal request: {:?}", &internal_request);
 
-        trace!("Spawn request added to queue");
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::Spawn(
+                internal_request,
+            )))?;
+
+            trace!("Spawn request added to queue");
+
+            response
+        };
+
+        await_completion(receiver).await?;
 
         Ok(response)
     }
@@ -113,20 +217,17 @@ impl ManageObjectService for ManageObjectServiceImpl {
         let request = request.into_inner();
         let SetObjectPositionSequenceRequest { requests } = request;
 
-        let mut set_object_responses: Vec<SetObjectPositionResponse> =
-            Vec::with_capacity(requests.len());
-
-        for (index, request) in requests.into_iter().enumerate() {
-            let response = self
-                .set_object_position(tonic::Request::new(request))
-                .await
-                .map_err(|e| {
-                    let errror_message = e.message();
-                    tonic::Status::new(e.code(), format!("Index {index}; {errror_message}"))
-                })?;
+        let receivers = enqueue_object_request_sequence(
+            requests,
+            set_position_request_to_internal_request,
+            ObjectRequest::SetPosition,
+        )?;
 
-            set_object_responses.push(response.into_inner());
-        }
+        let set_object_responses = await_sequence_completions(receivers)
+            .await?
+            .into_iter()
+            .map(|success| SetObjectPositionResponse { success })
+            .collect();
 
         Ok(Response::new(SetObjectPositionSequenceResponse {
             responses: set_object_responses,
@@ -144,156 +245,3141 @@ impl ManageObjectService for ManageObjectServiceImpl {
         let mut spawn_object_responses: Vec<SpawnObjectResponse> =
             Vec::with_capacity(requests.len());
 
-        for (index, request) in requests.into_iter().enumerate() {
-            let response = self
-                .spawn_object(tonic::Request::new(request))
-                .await
-                .map_err(|e| {
-                    let errror_message = e.message();
-                    tonic::Status::new(e.code(), format!("Index {index}; {errror_message}"))
-                })?;
+        let receivers = enqueue_object_request_sequence(
+            requests,
+            |request, completion| {
+                spawn_object_request_to_internal_request(request, completion, self.color_validation)
+            },
+            |internal_request| {
+                spawn_object_responses.push(SpawnObjectResponse {
+                    spawend_object_id: Some(object_id_to_proto(&internal_request.object_id)),
+                });
+                ObjectRequest::Spawn(internal_request)
+            },
+        )?;
 
-            spawn_object_responses.push(response.into_inner());
+        // Every entry is applied on its own, so the ID of each spawned object is reported even if
+        // others could not be spawned.
+        let successes = await_sequence_completions(receivers).await?;
+        for (response, success) in spawn_object_responses.iter_mut().zip(successes) {
+            if !success {
+                response.spawend_object_id = None;
+            }
         }
 
         Ok(Response::new(SpawnObjectSequenceResponse {
             responses: spawn_object_responses,
         }))
     }
-}
 
-#[derive(Error, Debug)]
-pub enum SetObjectPositionError {
-    #[error("Invalid object ID")]
-    InvalidObjectId,
-    #[error("Invalid position")]
-    InvalidPosition,
-}
+    #[doc = " Attaches an object to another one, or detaches it from its parent."]
+    async fn set_object_parent(
+        &self,
+        request: tonic::Request<SetObjectParentRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectParentResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
 
-/// Converts a gRPC SetObjectPositionRequest into an internal request, validating fields.
-pub fn set_position_request_to_internal_request(
-    set_position_request: SetObjectPositionRequest,
-) -> std::result::Result<request::object::SetObjectPositionRequest, SetObjectPositionError> {
-    let SetObjectPositionRequest {
-        object_id,
-        position,
-    } = set_position_request;
+        {
+            let _span = trace_span!("set_object_parent_rpc").entered();
 
-    trace!(
-        "Received request to set object position {:?} to {:?}",
-        object_id, position
-    );
+            let request = request.into_inner();
 
-    let object_id = object_id.ok_or(SetObjectPositionError::InvalidObjectId)?;
-    let uuid = object_id
-        .uuid
-        .ok_or(SetObjectPositionError::InvalidObjectId)?;
+            let internal_request =
+                match set_parent_request_to_internal_request(request, Some(completion)) {
+                    Ok(object) => object,
+                    Err(e) => {
+                        return match e {
+                            SetObjectParentError::InvalidObjectId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                            SetObjectParentError::InvalidParentId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                        };
+                    }
+                };
 
-    let position = position.ok_or(SetObjectPositionError::InvalidPosition)?;
+            trace!("Internal request: {:?}", &internal_request);
 
-    let internal_request = request::object::SetObjectPositionRequest {
-        object_id: request::object::ObjectId {
-            uuid: uuid::Uuid::from_slice(uuid.value.as_slice())
-                .map_err(|_| SetObjectPositionError::InvalidObjectId)?,
-        },
-        position: Vec3::new(position.x, position.y, position.z),
-    };
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::SetParent(
+                internal_request,
+            )))?;
 
-    Ok(internal_request)
-}
+            trace!("Set parent request added to queue");
+        }
 
-#[derive(Error, Debug)]
-pub enum SpawnObjectError {
-    #[error("Invalid object color")]
-    InvalidObjectColor,
-    #[error("Invalid object shape")]
-    InvalidObjectShape,
-    #[error("Invalid position")]
-    InvalidPosition,
-    #[error("Invalid object properties")]
-    InvalidObjectProperties,
-}
+        await_completion(receiver).await?;
 
-/// Converts a gRPC SpawnObjectRequest into an internal request, validating fields and assigning a UUID.
-pub fn spawn_object_request_to_internal_request(
-    spawn_object_request: SpawnObjectRequest,
-) -> std::result::Result<request::object::SpawnObjectRequest, SpawnObjectError> {
-    let SpawnObjectRequest {
-        object_properties,
-        position,
-    } = spawn_object_request;
+        Ok(Response::new(SetObjectParentResponse { success: true }))
+    }
 
-    trace!(
-        "Received request to spawn object {:?} at position {:?}",
-        object_properties, position
-    );
+    #[doc = " Sets how objects move towards the transforms they are sent, for one object or for every object"]
+    #[doc = " without a mode of its own."]
+    async fn set_interpolation(
+        &self,
+        request: tonic::Request<SetInterpolationRequest>,
+    ) -> std::result::Result<tonic::Response<SetInterpolationResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
 
-    let position = position.ok_or(SpawnObjectError::InvalidPosition)?;
+        {
+            let _span = trace_span!("set_interpolation_rpc").entered();
 
-    let object_properties = object_properties.ok_or(SpawnObjectError::InvalidObjectProperties)?;
+            let request = request.into_inner();
 
-    let object_color = object_properties
-        .color
-        .ok_or(SpawnObjectError::InvalidObjectColor)?;
+            let internal_request =
+                match set_interpolation_request_to_internal_request(request, Some(completion)) {
+                    Ok(object) => object,
+                    Err(e) => {
+                        return match e {
+                            SetInterpolationError::InvalidObjectId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                            SetInterpolationError::InvalidMode => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                        };
+                    }
+                };
 
-    let object_size = object_properties.size.unwrap_or(ObjectSize { value: 1.0 });
+            trace!("Internal request: {:?}", &internal_request);
 
-    let bevy_color =
-        normalize_object_color(object_color).map_err(|_| SpawnObjectError::InvalidObjectColor)?;
+            enqueue_internal_request(InternalRequest::MotionRequest(
+                MotionRequest::SetInterpolation(internal_request),
+            ))?;
 
-    let spawn_object_uuid = uuid::Uuid::now_v7();
+            trace!("Set interpolation request added to queue");
+        }
 
-    let spawn_request = request::object::SpawnObjectRequest {
-        object_id: request::object::ObjectId {
-            uuid: spawn_object_uuid,
-        },
-        object_properties: request::object::ObjectProperties {
-            color: bevy_color,
-            shape: match ObjectShape::try_from(object_properties.shape) {
-                Ok(ObjectShape::Cube) => request::object::ObjectShape::Cube,
-                Ok(ObjectShape::Sphere) => request::object::ObjectShape::Sphere,
-                _ => return Err(SpawnObjectError::InvalidObjectShape),
-            },
-            size: object_size.value,
-        },
-        position: Vec3::new(position.x, position.y, position.z),
-    };
+        await_completion(receiver).await?;
 
-    Ok(spawn_request)
-}
+        Ok(Response::new(SetInterpolationResponse { success: true }))
+    }
 
-/// Transforms a gRPC ObjectColor into a Bevy Color, validating values and enum variants.
-pub fn normalize_object_color(object_color: ObjectColor) -> anyhow::Result<bevy::color::Color> {
-    let ObjectColor { color } = object_color;
+    #[doc = " Plays keyframes on an object from the first one, replacing its current animation, or stops"]
+    #[doc = " the animation where it is if none is set. The object keeps the last animated color."]
+    async fn set_object_animation(
+        &self,
+        request: tonic::Request<SetObjectAnimationRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectAnimationResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
 
-    let color = color.ok_or_else(|| anyhow::anyhow!("Object color is None"))?;
+        {
+            let _span = trace_span!("set_object_animation_rpc").entered();
 
-    let bevy_color = match color {
-        object_color::Color::ColorEnum(n) => {
-            let color = ObjectColorEnum::try_from(n)?;
-            match color {
-                ObjectColorEnum::Blue => bevy::color::Color::srgb(0.0, 0.0, 1.0),
-                ObjectColorEnum::Green => bevy::color::Color::srgb(0.0, 1.0, 0.0),
-                ObjectColorEnum::Red => bevy::color::Color::srgb(1.0, 0.0, 0.0),
-                ObjectColorEnum::Unspecified => {
-                    warn!("Object color is unspecified");
-                    bevy::color::Color::srgb(1.0, 1.0, 1.0)
+            let request = request.into_inner();
+
+            let internal_request = match set_animation_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        SetObjectAnimationError::InvalidObjectId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetObjectAnimationError::InvalidAnimation => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
                 }
-            }
+            };
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::MotionRequest(
+                MotionRequest::SetObjectAnimation(internal_request),
+            ))?;
+
+            trace!("Set animation request added to queue");
         }
-        object_color::Color::ColorRgba(rgba) => {
-            trace!("Object color is RGBA: {:?}", rgba);
-            if (rgba.r > 1.0 || rgba.g > 1.0 || rgba.b > 1.0 || rgba.a > 1.0)
-                || (rgba.r < 0.0 || rgba.g < 0.0 || rgba.b < 0.0 || rgba.a < 0.0)
-            {
-                return Err(anyhow::anyhow!(
-                    "Object color rgba values must be between 0.0 and 1.0"
-                ));
-            }
 
-            bevy::color::Color::srgba(rgba.r, rgba.g, rgba.b, rgba.a)
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectAnimationResponse { success: true }))
+    }
+
+    #[doc = " Shows a text label above an object, or removes it if no label is set."]
+    async fn set_object_label(
+        &self,
+        request: tonic::Request<SetObjectLabelRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectLabelResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("set_object_label_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = match set_label_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        SetObjectLabelError::InvalidObjectId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetObjectLabelError::InvalidLabel => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetObjectLabelError::InvalidOffset => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
+                }
+            };
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::AnnotationRequest(
+                AnnotationRequest::SetObjectLabel(internal_request),
+            ))?;
+
+            trace!("Set label request added to queue");
         }
-    };
 
-    Ok(bevy_color)
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectLabelResponse { success: true }))
+    }
+
+    #[doc = " Shows free-floating text in the scene, replacing the annotation with the same ID."]
+    async fn set_annotation(
+        &self,
+        request: tonic::Request<SetAnnotationRequest>,
+    ) -> std::result::Result<tonic::Response<SetAnnotationResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        let response = {
+            let _span = trace_span!("set_annotation_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = match set_annotation_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        SetAnnotationError::InvalidAnnotationId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetAnnotationError::InvalidText => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetAnnotationError::InvalidPosition => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
+                }
+            };
+
+            let response = Response::new(SetAnnotationResponse {
+                annotation_id: Some(annotation_id_to_proto(&internal_request.annotation_id)),
+            });
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::AnnotationRequest(
+                AnnotationRequest::SetAnnotation(internal_request),
+            ))?;
+
+            trace!("Set annotation request added to queue");
+
+            response
+        };
+
+        await_completion(receiver).await?;
+
+        Ok(response)
+    }
+
+    #[doc = " Removes an annotation from the scene."]
+    async fn remove_annotation(
+        &self,
+        request: tonic::Request<RemoveAnnotationRequest>,
+    ) -> std::result::Result<tonic::Response<RemoveAnnotationResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("remove_annotation_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request =
+                match remove_annotation_request_to_internal_request(request, Some(completion)) {
+                    Ok(object) => object,
+                    Err(e) => {
+                        return match e {
+                            RemoveAnnotationError::InvalidAnnotationId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                        };
+                    }
+                };
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::AnnotationRequest(
+                AnnotationRequest::RemoveAnnotation(internal_request),
+            ))?;
+
+            trace!("Remove annotation request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(RemoveAnnotationResponse { success: true }))
+    }
+
+    #[doc = " Draws a fading trail behind an object, or removes it if no trail is set."]
+    async fn set_object_trail(
+        &self,
+        request: tonic::Request<SetObjectTrailRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectTrailResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("set_object_trail_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = match set_trail_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        SetObjectTrailError::InvalidObjectId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetObjectTrailError::InvalidTrail => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
+                }
+            };
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::TrailRequest(TrailRequest::SetObjectTrail(
+                internal_request,
+            )))?;
+
+            trace!("Set trail request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectTrailResponse { success: true }))
+    }
+
+    #[doc = " Draws a line, arrow or polyline, replacing the primitive with the same ID."]
+    async fn set_primitive(
+        &self,
+        request: tonic::Request<SetPrimitiveRequest>,
+    ) -> std::result::Result<tonic::Response<SetPrimitiveResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        let response = {
+            let _span = trace_span!("set_primitive_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = match set_primitive_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        SetPrimitiveError::InvalidPrimitiveId => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetPrimitiveError::InvalidShape => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetPrimitiveError::InvalidColor => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                        SetPrimitiveError::InvalidLifetime => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
+                }
+            };
+
+            let response = Response::new(SetPrimitiveResponse {
+                primitive_id: Some(primitive_id_to_proto(&internal_request.primitive_id)),
+            });
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::PrimitiveRequest(
+                PrimitiveRequest::SetPrimitive(internal_request),
+            ))?;
+
+            trace!("Set primitive request added to queue");
+
+            response
+        };
+
+        await_completion(receiver).await?;
+
+        Ok(response)
+    }
+
+    #[doc = " Removes a primitive."]
+    async fn remove_primitive(
+        &self,
+        request: tonic::Request<RemovePrimitiveRequest>,
+    ) -> std::result::Result<tonic::Response<RemovePrimitiveResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("remove_primitive_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request =
+                match remove_primitive_request_to_internal_request(request, Some(completion)) {
+                    Ok(object) => object,
+                    Err(e) => {
+                        return match e {
+                            RemovePrimitiveError::InvalidPrimitiveId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                        };
+                    }
+                };
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::PrimitiveRequest(
+                PrimitiveRequest::RemovePrimitive(internal_request),
+            ))?;
+
+            trace!("Remove primitive request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(RemovePrimitiveResponse { success: true }))
+    }
+
+    #[doc = " Removes every primitive."]
+    async fn clear_primitives(
+        &self,
+        _request: tonic::Request<ClearPrimitivesRequest>,
+    ) -> std::result::Result<tonic::Response<ClearPrimitivesResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("clear_primitives_rpc").entered();
+
+            enqueue_internal_request(InternalRequest::PrimitiveRequest(
+                PrimitiveRequest::ClearPrimitives(request::primitive::ClearPrimitivesRequest {
+                    completion: Some(completion),
+                }),
+            ))?;
+
+            trace!("Clear primitives request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(ClearPrimitivesResponse { success: true }))
+    }
+
+    #[doc = " Despawns an object from the scene, together with the objects attached to it."]
+    async fn despawn_object(
+        &self,
+        request: tonic::Request<DespawnObjectRequest>,
+    ) -> std::result::Result<tonic::Response<DespawnObjectResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("despawn_object_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request =
+                match despawn_object_request_to_internal_request(request, Some(completion)) {
+                    Ok(object) => object,
+                    Err(e) => {
+                        return match e {
+                            DespawnObjectError::InvalidObjectId => {
+                                Err(tonic::Status::invalid_argument(e.to_string()))
+                            }
+                        };
+                    }
+                };
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::Despawn(
+                internal_request,
+            )))?;
+
+            trace!("Despawn request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(DespawnObjectResponse { success: true }))
+    }
+
+    #[doc = " Despawns multiple objects in a single request."]
+    async fn despawn_object_sequence(
+        &self,
+        request: tonic::Request<DespawnObjectSequenceRequest>,
+    ) -> std::result::Result<tonic::Response<DespawnObjectSequenceResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let DespawnObjectSequenceRequest { requests } = request;
+
+        let receivers = enqueue_object_request_sequence(
+            requests,
+            despawn_object_request_to_internal_request,
+            ObjectRequest::Despawn,
+        )?;
+
+        let despawn_object_responses = await_sequence_completions(receivers)
+            .await?
+            .into_iter()
+            .map(|success| DespawnObjectResponse { success })
+            .collect();
+
+        Ok(Response::new(DespawnObjectSequenceResponse {
+            responses: despawn_object_responses,
+        }))
+    }
+
+    #[doc = " Sets the rotation of the object."]
+    async fn set_object_rotation(
+        &self,
+        request: tonic::Request<SetObjectRotationRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectRotationResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("set_object_rotation_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request =
+                set_rotation_request_to_internal_request(request, Some(completion))
+                    .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::SetTransform(
+                internal_request,
+            )))?;
+
+            trace!("Set rotation request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectRotationResponse { success: true }))
+    }
+
+    #[doc = " Sets the scale of the object."]
+    async fn set_object_scale(
+        &self,
+        request: tonic::Request<SetObjectScaleRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectScaleResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("set_object_scale_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = set_scale_request_to_internal_request(request, Some(completion))
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::SetTransform(
+                internal_request,
+            )))?;
+
+            trace!("Set scale request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectScaleResponse { success: true }))
+    }
+
+    #[doc = " Sets the position, rotation and scale of the object at once."]
+    async fn set_object_transform(
+        &self,
+        request: tonic::Request<SetObjectTransformRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectTransformResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("set_object_transform_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request =
+                set_transform_request_to_internal_request(request, Some(completion))
+                    .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::SetTransform(
+                internal_request,
+            )))?;
+
+            trace!("Set transform request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectTransformResponse { success: true }))
+    }
+
+    #[doc = " Sets the transform of multiple objects in a sequence."]
+    async fn set_object_transform_sequence(
+        &self,
+        request: tonic::Request<SetObjectTransformSequenceRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectTransformSequenceResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let SetObjectTransformSequenceRequest { requests } = request;
+
+        let receivers = enqueue_object_request_sequence(
+            requests,
+            set_transform_request_to_internal_request,
+            ObjectRequest::SetTransform,
+        )?;
+
+        let set_transform_responses = await_sequence_completions(receivers)
+            .await?
+            .into_iter()
+            .map(|success| SetObjectTransformResponse { success })
+            .collect();
+
+        Ok(Response::new(SetObjectTransformSequenceResponse {
+            responses: set_transform_responses,
+        }))
+    }
+
+    #[doc = " Changes the shape, color or size of an existing object."]
+    async fn set_object_properties(
+        &self,
+        request: tonic::Request<SetObjectPropertiesRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectPropertiesResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        {
+            let _span = trace_span!("set_object_properties_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = set_properties_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            )
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::ObjectRequest(
+                ObjectRequest::SetProperties(internal_request),
+            ))?;
+
+            trace!("Set properties request added to queue");
+        }
+
+        await_completion(receiver).await?;
+
+        Ok(Response::new(SetObjectPropertiesResponse { success: true }))
+    }
+
+    #[doc = " Changes the properties of multiple objects in a sequence."]
+    async fn set_object_properties_sequence(
+        &self,
+        request: tonic::Request<SetObjectPropertiesSequenceRequest>,
+    ) -> std::result::Result<tonic::Response<SetObjectPropertiesSequenceResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let SetObjectPropertiesSequenceRequest { requests } = request;
+
+        let receivers = enqueue_object_request_sequence(
+            requests,
+            |request, completion| {
+                set_properties_request_to_internal_request(
+                    request,
+                    completion,
+                    self.color_validation,
+                )
+            },
+            ObjectRequest::SetProperties,
+        )?;
+
+        let set_properties_responses = await_sequence_completions(receivers)
+            .await?
+            .into_iter()
+            .map(|success| SetObjectPropertiesResponse { success })
+            .collect();
+
+        Ok(Response::new(SetObjectPropertiesSequenceResponse {
+            responses: set_properties_responses,
+        }))
+    }
+
+    #[doc = " Gets the current state of an object."]
+    async fn get_object(
+        &self,
+        request: tonic::Request<GetObjectRequest>,
+    ) -> std::result::Result<tonic::Response<GetObjectResponse>, tonic::Status> {
+        let (response, receiver) = ResponseSender::channel();
+
+        {
+            let _span = trace_span!("get_object_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = get_object_request_to_internal_request(request, response)
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::QueryRequest(QueryRequest::GetObject(
+                internal_request,
+            )))?;
+
+            trace!("Get object request added to queue");
+        }
+
+        let snapshot = receiver
+            .await
+            .map_err(|_| tonic::Status::unavailable("The viewer did not answer the request"))?
+            .ok_or_else(|| tonic::Status::not_found("Object not found"))?;
+
+        Ok(Response::new(GetObjectResponse {
+            object: Some(object_snapshot_to_object_state(snapshot)),
+        }))
+    }
+
+    #[doc = " Lists the objects in the scene, ordered by ID."]
+    async fn list_objects(
+        &self,
+        request: tonic::Request<ListObjectsRequest>,
+    ) -> std::result::Result<tonic::Response<ListObjectsResponse>, tonic::Status> {
+        let (response, receiver) = ResponseSender::channel();
+
+        {
+            let _span = trace_span!("list_objects_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = list_objects_request_to_internal_request(request, response)
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::QueryRequest(QueryRequest::ListObjects(
+                internal_request,
+            )))?;
+
+            trace!("List objects request added to queue");
+        }
+
+        let ObjectPage {
+            objects,
+            next_start_after,
+        } = receiver
+            .await
+            .map_err(|_| tonic::Status::unavailable("The viewer did not answer the request"))?;
+
+        Ok(Response::new(ListObjectsResponse {
+            objects: objects
+                .into_iter()
+                .map(object_snapshot_to_object_state)
+                .collect(),
+            next_page_token: next_start_after
+                .map(|object_id| object_id.uuid.to_string())
+                .unwrap_or_default(),
+        }))
+    }
+
+    #[doc = " Gets the state of every object in the scene, taken within a single frame."]
+    async fn get_scene_snapshot(
+        &self,
+        _request: tonic::Request<GetSceneSnapshotRequest>,
+    ) -> std::result::Result<tonic::Response<GetSceneSnapshotResponse>, tonic::Status> {
+        let (response, receiver) = ResponseSender::channel();
+
+        {
+            let _span = trace_span!("get_scene_snapshot_rpc").entered();
+
+            enqueue_internal_request(InternalRequest::QueryRequest(
+                QueryRequest::GetSceneSnapshot(request::query::GetSceneSnapshotRequest {
+                    response,
+                }),
+            ))?;
+
+            trace!("Get scene snapshot request added to queue");
+        }
+
+        let snapshot = receiver
+            .await
+            .map_err(|_| tonic::Status::unavailable("The viewer did not answer the request"))?;
+
+        Ok(Response::new(GetSceneSnapshotResponse {
+            objects: snapshot
+                .objects
+                .into_iter()
+                .map(object_snapshot_to_object_state)
+                .collect(),
+            frame: snapshot.frame,
+        }))
+    }
+
+    #[doc = " Streams object changes as they are applied by the viewer."]
+    async fn watch_objects(
+        &self,
+        request: tonic::Request<WatchObjectsRequest>,
+    ) -> std::result::Result<tonic::Response<Self::WatchObjectsStream>, tonic::Status> {
+        let _span = trace_span!("watch_objects_rpc").entered();
+
+        let request = request.into_inner();
+
+        let filter = watch_objects_request_to_filter(request)
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+        let receiver = OBJECT_CHANGE_BROADCAST.subscribe();
+
+        trace!("Watcher subscribed to object changes");
+
+        let stream = BroadcastStream::new(receiver).filter_map(move |notification| {
+            match notification {
+                Ok(notification) => {
+                    if filter.is_empty()
+                        || filter.contains(&notification.object_changed.object_id.uuid)
+                    {
+                        Some(Ok(object_change_notification_to_object_event(notification)))
+                    } else {
+                        None
+                    }
+                }
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    warn!("Watcher lagged behind by {} object changes", skipped);
+                    Some(Err(tonic::Status::data_loss(format!(
+                        "Watcher lagged behind and missed {skipped} object changes; resynchronize with GetSceneSnapshot"
+                    ))))
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[doc = " Registers a mesh that objects with the shape OBJECT_SHAPE_MESH can refer to by its ID."]
+    async fn register_mesh(
+        &self,
+        request: tonic::Request<RegisterMeshRequest>,
+    ) -> std::result::Result<tonic::Response<RegisterMeshResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        let request = request.into_inner();
+
+        // Parsing a large model takes a while, so keep it off the threads serving other requests.
+        let internal_request = tokio::task::spawn_blocking(move || {
+            let _span = trace_span!("register_mesh_rpc").entered();
+            register_mesh_request_to_internal_request(request, Some(completion))
+        })
+        .await
+        .map_err(|_| tonic::Status::internal("Failed to parse the mesh"))?
+        .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+        let response = Response::new(RegisterMeshResponse {
+            mesh_id: Some(mesh_id_to_proto(&internal_request.mesh_id)),
+        });
+
+        trace!("Register mesh request for {}", internal_request.mesh_id);
+
+        enqueue_internal_request(InternalRequest::ObjectRequest(ObjectRequest::RegisterMesh(
+            internal_request,
+        )))?;
+
+        trace!("Register mesh request added to queue");
+
+        await_completion(receiver).await?;
+
+        Ok(response)
+    }
+
+    #[doc = " Applies spawns, transform and property changes and despawns together, in order, within a single"]
+    #[doc = " frame, so that observers never see part of them. Every operation is checked first; if any would"]
+    #[doc = " fail, none is applied and the batch fails with the status that operation would have had."]
+    #[doc = " Despawning an object despawns its descendants too, so later operations cannot refer to them."]
+    async fn apply_batch(
+        &self,
+        request: tonic::Request<BatchRequest>,
+    ) -> std::result::Result<tonic::Response<BatchResponse>, tonic::Status> {
+        let (completion, receiver) = Completion::channel();
+
+        let response = {
+            let _span = trace_span!("apply_batch_rpc").entered();
+
+            let request = request.into_inner();
+
+            let internal_request = match batch_request_to_internal_request(
+                request,
+                Some(completion),
+                self.color_validation,
+            ) {
+                Ok(object) => object,
+                Err(e) => {
+                    return match e {
+                        BatchError::InvalidOperation { .. } => {
+                            Err(tonic::Status::invalid_argument(e.to_string()))
+                        }
+                    };
+                }
+            };
+
+            let response = Response::new(BatchResponse {
+                spawned_object_ids: internal_request
+                    .operations
+                    .iter()
+                    .filter_map(|operation| match operation {
+                        request::batch::BatchOperation::Spawn(spawn) => {
+                            Some(object_id_to_proto(&spawn.object_id))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            });
+
+            trace!("Internal request: {:?}", &internal_request);
+
+            enqueue_internal_request(InternalRequest::BatchRequest(
+                request::batch::BatchRequest::ApplyBatch(internal_request),
+            ))?;
+
+            trace!("Batch request added to queue");
+
+            response
+        };
+
+        await_completion(receiver).await?;
+
+        Ok(response)
+    }
+
+    #[doc = " Applies batches of transform updates sent over a single long-lived stream."]
+    async fn stream_object_updates(
+        &self,
+        request: tonic::Request<tonic::Streaming<ObjectUpdateBatch>>,
+    ) -> std::result::Result<tonic::Response<Self::StreamObjectUpdatesStream>, tonic::Status> {
+        let inbound = request.into_inner();
+
+        trace!("Object update stream opened");
+
+        // Batches are applied as the acknowledgement stream is polled. When the viewer falls
+        // behind, queueing waits for room instead of failing, and HTTP/2 flow control passes the
+        // backpressure on to the client.
+        let stream = inbound.then(|batch| async move {
+            match batch {
+                Ok(batch) => Ok(apply_object_update_batch(batch).await),
+                Err(e) => Err(e),
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Queues every valid update of a batch, waiting for room in the queue, then waits for the viewer to
+/// apply them and reports the ones that were invalid or could not be applied.
+async fn apply_object_update_batch(batch: ObjectUpdateBatch) -> ObjectUpdateAck {
+    let ObjectUpdateBatch { sequence, updates } = batch;
+
+    let mut failures = Vec::new();
+    let mut receivers = Vec::with_capacity(updates.len());
+
+    for (index, update) in updates.into_iter().enumerate() {
+        let (completion, receiver) = Completion::channel();
+        match set_transform_request_to_internal_request(update, Some(completion)) {
+            Ok(internal_request) => {
+                INTERNAL_REQUEST_QUEUE
+                    .push(InternalRequest::ObjectRequest(ObjectRequest::SetTransform(
+                        internal_request,
+                    )))
+                    .await;
+                receivers.push((index, receiver));
+            }
+            Err(e) => failures.push(ObjectUpdateFailure {
+                index: index as u32,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    let mut accepted = 0;
+    for (index, receiver) in receivers {
+        match await_completion(receiver).await {
+            Ok(()) => accepted += 1,
+            Err(status) => failures.push(ObjectUpdateFailure {
+                index: index as u32,
+                message: status.message().to_string(),
+            }),
+        }
+    }
+    failures.sort_by_key(|failure| failure.index);
+
+    trace!(
+        "Object update batch {} applied {} updates, rejected {}",
+        sequence,
+        accepted,
+        failures.len()
+    );
+
+    ObjectUpdateAck {
+        sequence,
+        accepted,
+        failures,
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EnqueueError {
+    #[error("The viewer is falling behind on requests; retry after a short delay")]
+    QueueFull,
+}
+
+impl From<EnqueueError> for tonic::Status {
+    /// Maps a full queue to `RESOURCE_EXHAUSTED` so clients know to back off.
+    fn from(e: EnqueueError) -> Self {
+        match e {
+            EnqueueError::QueueFull => tonic::Status::resource_exhausted(e.to_string()),
+        }
+    }
+}
+
+/// Adds a request to the internal request queue without waiting, failing when the viewer has fallen behind.
+fn enqueue_internal_request(request: InternalRequest) -> std::result::Result<(), EnqueueError> {
+    INTERNAL_REQUEST_QUEUE.try_push(request).map_err(|_| {
+        warn!("Internal request queue is full; rejecting request");
+        EnqueueError::QueueFull
+    })
+}
+
+/// Receives the outcome of an object request from the viewer.
+type CompletionReceiver = oneshot::Receiver<std::result::Result<(), ObjectRequestError>>;
+
+/// Maps an object request that the viewer could not apply to the matching gRPC status.
+fn object_request_error_to_status(e: ObjectRequestError) -> tonic::Status {
+    match e {
+        ObjectRequestError::NotFound(_) => tonic::Status::not_found(e.to_string()),
+        ObjectRequestError::AlreadyExists(_) => tonic::Status::already_exists(e.to_string()),
+        ObjectRequestError::DimensionsMismatch(_) => {
+            tonic::Status::failed_precondition(e.to_string())
+        }
+        ObjectRequestError::ParentCycle(_) => tonic::Status::failed_precondition(e.to_string()),
+        ObjectRequestError::MeshNotFound(_) => tonic::Status::not_found(e.to_string()),
+        ObjectRequestError::AnnotationNotFound(_) => tonic::Status::not_found(e.to_string()),
+        ObjectRequestError::PrimitiveNotFound(_) => tonic::Status::not_found(e.to_string()),
+        ObjectRequestError::MeshAlreadyExists(_) => tonic::Status::already_exists(e.to_string()),
+        // The batch fails with the status of the operation that could not be applied.
+        ObjectRequestError::BatchOperation { ref error, .. } => {
+            let code = object_request_error_to_status(*error.clone()).code();
+            tonic::Status::new(code, e.to_string())
+        }
+    }
+}
+
+/// Waits until the viewer has applied an object request and reports its outcome.
+async fn await_completion(receiver: CompletionReceiver) -> std::result::Result<(), tonic::Status> {
+    receiver
+        .await
+        .map_err(|_| tonic::Status::unavailable("The viewer did not answer the request"))?
+        .map_err(object_request_error_to_status)
+}
+
+#[derive(Error, Debug)]
+pub enum ObjectRequestSequenceError {
+    #[error("Index {index}; {message}")]
+    InvalidRequest { index: usize, message: String },
+    #[error("Sequence of {len} requests exceeds the limit of {limit}")]
+    TooLong { len: usize, limit: usize },
+    #[error("{source}")]
+    Enqueue { source: EnqueueError },
+}
+
+impl From<ObjectRequestSequenceError> for tonic::Status {
+    /// Maps a rejected sequence to the status its failing entry would have produced on its own.
+    fn from(e: ObjectRequestSequenceError) -> Self {
+        match e {
+            ObjectRequestSequenceError::InvalidRequest { .. }
+            | ObjectRequestSequenceError::TooLong { .. } => {
+                tonic::Status::invalid_argument(e.to_string())
+            }
+            ObjectRequestSequenceError::Enqueue { .. } => {
+                tonic::Status::resource_exhausted(e.to_string())
+            }
+        }
+    }
+}
+
+/// Validates every request of a sequence before queueing any of them, and queues all of them or
+/// none, so an invalid entry or a full queue leaves the scene untouched.
+fn enqueue_object_request_sequence<T, R, E: std::fmt::Display>(
+    requests: Vec<T>,
+    to_internal_request: impl Fn(T, Option<Completion>) -> std::result::Result<R, E>,
+    mut to_object_request: impl FnMut(R) -> ObjectRequest,
+) -> std::result::Result<Vec<CompletionReceiver>, ObjectRequestSequenceError> {
+    // A sequence that can never fit in the queue must not be reported as a retryable failure.
+    if requests.len() > InternalRequestQueue::CAPACITY {
+        return Err(ObjectRequestSequenceError::TooLong {
+            len: requests.len(),
+            limit: InternalRequestQueue::CAPACITY,
+        });
+    }
+
+    let mut internal_requests = Vec::with_capacity(requests.len());
+    let mut receivers = Vec::with_capacity(requests.len());
+
+    for (index, request) in requests.into_iter().enumerate() {
+        let (completion, receiver) = Completion::channel();
+        let internal_request = to_internal_request(request, Some(completion)).map_err(|e| {
+            ObjectRequestSequenceError::InvalidRequest {
+                index,
+                message: e.to_string(),
+            }
+        })?;
+
+        internal_requests.push(to_object_request(internal_request));
+        receivers.push(receiver);
+    }
+
+    INTERNAL_REQUEST_QUEUE
+        .try_push_all(
+            internal_requests
+                .into_iter()
+                .map(InternalRequest::ObjectRequest)
+                .collect(),
+        )
+        .map_err(|_| {
+            warn!("Internal request queue is full; rejecting request sequence");
+            ObjectRequestSequenceError::Enqueue {
+                source: EnqueueError::QueueFull,
+            }
+        })?;
+
+    Ok(receivers)
+}
+
+/// Waits for every request of a sequence, reporting unknown objects as unsuccessful entries.
+async fn await_sequence_completions(
+    receivers: Vec<CompletionReceiver>,
+) -> std::result::Result<Vec<bool>, tonic::Status> {
+    let mut results = Vec::with_capacity(receivers.len());
+
+    for (index, receiver) in receivers.into_iter().enumerate() {
+        let success = match receiver.await {
+            Ok(Ok(())) => true,
+            Ok(Err(e)) => {
+                trace!("Index {index}; {e}");
+                false
+            }
+            Err(_) => {
+                return Err(tonic::Status::unavailable(format!(
+                    "Index {index}; The viewer did not answer the request"
+                )));
+            }
+        };
+        results.push(success);
+    }
+
+    Ok(results)
+}
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("Operation {index} of the batch: {message}")]
+    InvalidOperation { index: usize, message: String },
+}
+
+/// Converts a gRPC BatchRequest into an internal request, rejecting it whole if any operation is invalid.
+pub fn batch_request_to_internal_request(
+    batch_request: BatchRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::batch::ApplyBatchRequest, BatchError> {
+    let BatchRequest { operations } = batch_request;
+
+    trace!(
+        "Received request to apply a batch of {} operations",
+        operations.len()
+    );
+
+    let operations = operations
+        .into_iter()
+        .enumerate()
+        .map(|(index, operation)| {
+            batch_operation_to_internal_operation(operation, color_validation)
+                .map_err(|message| BatchError::InvalidOperation { index, message })
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let internal_request = request::batch::ApplyBatchRequest {
+        operations,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Converts one operation of a gRPC batch, describing why it is invalid if it cannot be.
+fn batch_operation_to_internal_operation(
+    batch_operation: BatchOperation,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::batch::BatchOperation, String> {
+    use batch_operation::Operation;
+    use request::batch::BatchOperation as Internal;
+
+    match batch_operation.operation {
+        Some(Operation::Spawn(spawn)) => {
+            spawn_object_request_to_internal_request(spawn, None, color_validation)
+                .map(Internal::Spawn)
+                .map_err(|e| e.to_string())
+        }
+        Some(Operation::SetTransform(set_transform)) => {
+            set_transform_request_to_internal_request(set_transform, None)
+                .map(Internal::SetTransform)
+                .map_err(|e| e.to_string())
+        }
+        Some(Operation::SetProperties(set_properties)) => {
+            set_properties_request_to_internal_request(set_properties, None, color_validation)
+                .map(Internal::SetProperties)
+                .map_err(|e| e.to_string())
+        }
+        Some(Operation::Despawn(despawn)) => {
+            despawn_object_request_to_internal_request(despawn, None)
+                .map(Internal::Despawn)
+                .map_err(|e| e.to_string())
+        }
+        None => Err("Missing operation".to_string()),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectPositionError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid position")]
+    InvalidPosition,
+    #[error("Invalid timestamp")]
+    InvalidTimestamp,
+}
+
+/// Converts a gRPC SetObjectPositionRequest into an internal request, validating fields.
+pub fn set_position_request_to_internal_request(
+    set_position_request: SetObjectPositionRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::SetObjectPositionRequest, SetObjectPositionError> {
+    let SetObjectPositionRequest {
+        object_id,
+        position,
+        timestamp,
+    } = set_position_request;
+
+    trace!(
+        "Received request to set object position {:?} to {:?} at {:?}",
+        object_id, position, timestamp
+    );
+
+    let object_id = object_id.ok_or(SetObjectPositionError::InvalidObjectId)?;
+    let uuid = object_id
+        .uuid
+        .ok_or(SetObjectPositionError::InvalidObjectId)?;
+
+    let position = position
+        .and_then(normalize_vector3)
+        .ok_or(SetObjectPositionError::InvalidPosition)?;
+    let timestamp = timestamp
+        .map(|timestamp| {
+            normalize_timestamp(timestamp).ok_or(SetObjectPositionError::InvalidTimestamp)
+        })
+        .transpose()?;
+
+    let internal_request = request::object::SetObjectPositionRequest {
+        object_id: request::object::ObjectId {
+            uuid: uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| SetObjectPositionError::InvalidObjectId)?,
+        },
+        position,
+        timestamp,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectTransformError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid position")]
+    InvalidPosition,
+    #[error("Invalid rotation")]
+    InvalidRotation,
+    #[error("Invalid scale")]
+    InvalidScale,
+    #[error("Invalid timestamp")]
+    InvalidTimestamp,
+    #[error("Invalid velocity")]
+    InvalidVelocity,
+    #[error("Invalid angular velocity")]
+    InvalidAngularVelocity,
+}
+
+/// Converts a gRPC SetObjectRotationRequest into an internal transform request that only updates the rotation.
+pub fn set_rotation_request_to_internal_request(
+    set_rotation_request: SetObjectRotationRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::SetObjectTransformRequest, SetObjectTransformError> {
+    let SetObjectRotationRequest {
+        object_id,
+        rotation,
+    } = set_rotation_request;
+
+    set_transform_request_to_internal_request(
+        SetObjectTransformRequest {
+            object_id,
+            position: None,
+            rotation: Some(rotation.ok_or(SetObjectTransformError::InvalidRotation)?),
+            scale: None,
+            timestamp: None,
+            velocity: None,
+            angular_velocity: None,
+        },
+        completion,
+    )
+}
+
+/// Converts a gRPC SetObjectScaleRequest into an internal transform request that only updates the scale.
+pub fn set_scale_request_to_internal_request(
+    set_scale_request: SetObjectScaleRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::SetObjectTransformRequest, SetObjectTransformError> {
+    let SetObjectScaleRequest { object_id, scale } = set_scale_request;
+
+    set_transform_request_to_internal_request(
+        SetObjectTransformRequest {
+            object_id,
+            position: None,
+            rotation: None,
+            scale: Some(scale.ok_or(SetObjectTransformError::InvalidScale)?),
+            timestamp: None,
+            velocity: None,
+            angular_velocity: None,
+        },
+        completion,
+    )
+}
+
+/// Converts a gRPC SetObjectTransformRequest into an internal request, validating and normalizing fields.
+pub fn set_transform_request_to_internal_request(
+    set_transform_request: SetObjectTransformRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::SetObjectTransformRequest, SetObjectTransformError> {
+    let SetObjectTransformRequest {
+        object_id,
+        position,
+        rotation,
+        scale,
+        timestamp,
+        velocity,
+        angular_velocity,
+    } = set_transform_request;
+
+    trace!(
+        "Received request to set object transform {:?} to position {:?}, rotation {:?}, scale {:?} at {:?} with velocity {:?} and angular velocity {:?}",
+        object_id, position, rotation, scale, timestamp, velocity, angular_velocity
+    );
+
+    let object_id = object_id.ok_or(SetObjectTransformError::InvalidObjectId)?;
+    let uuid = object_id
+        .uuid
+        .ok_or(SetObjectTransformError::InvalidObjectId)?;
+
+    let position = position
+        .map(|position| normalize_vector3(position).ok_or(SetObjectTransformError::InvalidPosition))
+        .transpose()?;
+    let rotation = rotation
+        .map(|rotation| {
+            normalize_rotation(rotation).ok_or(SetObjectTransformError::InvalidRotation)
+        })
+        .transpose()?;
+    let scale = scale
+        .map(|scale| normalize_vector3(scale).ok_or(SetObjectTransformError::InvalidScale))
+        .transpose()?;
+    let timestamp = timestamp
+        .map(|timestamp| {
+            normalize_timestamp(timestamp).ok_or(SetObjectTransformError::InvalidTimestamp)
+        })
+        .transpose()?;
+    let velocity = velocity
+        .map(|velocity| normalize_vector3(velocity).ok_or(SetObjectTransformError::InvalidVelocity))
+        .transpose()?;
+    let angular_velocity = angular_velocity
+        .map(|angular_velocity| {
+            normalize_vector3(angular_velocity)
+                .ok_or(SetObjectTransformError::InvalidAngularVelocity)
+        })
+        .transpose()?;
+
+    let internal_request = request::object::SetObjectTransformRequest {
+        object_id: request::object::ObjectId {
+            uuid: uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| SetObjectTransformError::InvalidObjectId)?,
+        },
+        position,
+        rotation,
+        scale,
+        timestamp,
+        velocity,
+        angular_velocity,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Validates a simulation timestamp in seconds, rejecting non-finite values.
+pub fn normalize_timestamp(timestamp: f64) -> Option<f64> {
+    timestamp.is_finite().then_some(timestamp)
+}
+
+/// Converts a gRPC Vector3 into a Bevy Vec3, rejecting non-finite components.
+pub fn normalize_vector3(vector: Vector3) -> Option<Vec3> {
+    let vec = Vec3::new(vector.x, vector.y, vector.z);
+    vec.is_finite().then_some(vec)
+}
+
+/// Converts a gRPC Quaternion into a normalized Bevy Quat, rejecting zero-length or non-finite values.
+pub fn normalize_rotation(rotation: Quaternion) -> Option<Quat> {
+    let quat = Quat::from_xyzw(rotation.x, rotation.y, rotation.z, rotation.w);
+    if !quat.is_finite() || quat.length_squared() <= f32::EPSILON {
+        return None;
+    }
+    Some(quat.normalize())
+}
+
+#[derive(Error, Debug)]
+pub enum SpawnObjectError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid object color")]
+    InvalidObjectColor,
+    #[error("Invalid object shape")]
+    InvalidObjectShape,
+    #[error("Invalid position")]
+    InvalidPosition,
+    #[error("Invalid object properties")]
+    InvalidObjectProperties,
+    #[error("Invalid object dimensions")]
+    InvalidObjectDimensions,
+    #[error("Invalid object size")]
+    InvalidObjectSize,
+    #[error("Invalid object material")]
+    InvalidObjectMaterial,
+    #[error("Invalid parent ID")]
+    InvalidParentId,
+}
+
+/// Converts a gRPC SpawnObjectRequest into an internal request, validating fields and assigning a UUID if none was given.
+pub fn spawn_object_request_to_internal_request(
+    spawn_object_request: SpawnObjectRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::object::SpawnObjectRequest, SpawnObjectError> {
+    let SpawnObjectRequest {
+        object_properties,
+        position,
+        object_id,
+        parent,
+    } = spawn_object_request;
+
+    trace!(
+        "Received request to spawn object {:?} with ID {:?} at position {:?} under parent {:?}",
+        object_properties, object_id, position, parent
+    );
+
+    let spawn_object_uuid = match object_id {
+        Some(object_id) => {
+            let uuid = object_id.uuid.ok_or(SpawnObjectError::InvalidObjectId)?;
+            uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| SpawnObjectError::InvalidObjectId)?
+        }
+        None => uuid::Uuid::now_v7(),
+    };
+
+    let position = position
+        .and_then(normalize_vector3)
+        .ok_or(SpawnObjectError::InvalidPosition)?;
+
+    let parent = parent
+        .map(|parent| normalize_object_id(parent).ok_or(SpawnObjectError::InvalidParentId))
+        .transpose()?;
+
+    let object_properties = object_properties.ok_or(SpawnObjectError::InvalidObjectProperties)?;
+
+    let object_color = object_properties
+        .color
+        .ok_or(SpawnObjectError::InvalidObjectColor)?;
+
+    let object_size = object_properties
+        .size
+        .map(|size| normalize_object_size(size).ok_or(SpawnObjectError::InvalidObjectSize))
+        .transpose()?
+        .unwrap_or(request::object::ObjectSize::Uniform(1.0));
+
+    let bevy_color = normalize_object_color(object_color, color_validation)
+        .map_err(|_| SpawnObjectError::InvalidObjectColor)?;
+
+    let shape = normalize_object_shape(object_properties.shape, object_properties.mesh_id)
+        .ok_or(SpawnObjectError::InvalidObjectShape)?;
+
+    let dimensions = object_properties
+        .dimensions
+        .map(|dimensions| {
+            normalize_object_dimensions(dimensions)
+                .filter(|dimensions| dimensions.shape() == shape)
+                .ok_or(SpawnObjectError::InvalidObjectDimensions)
+        })
+        .transpose()?;
+
+    let material = object_properties
+        .material
+        .map(|material| {
+            normalize_object_material(material, color_validation)
+                .ok_or(SpawnObjectError::InvalidObjectMaterial)
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let spawn_request = request::object::SpawnObjectRequest {
+        object_id: request::object::ObjectId {
+            uuid: spawn_object_uuid,
+        },
+        object_properties: request::object::ObjectProperties {
+            color: bevy_color,
+            material,
+            shape,
+            size: object_size,
+            dimensions,
+        },
+        position,
+        parent,
+        completion,
+    };
+
+    Ok(spawn_request)
+}
+
+#[derive(Error, Debug)]
+pub enum DespawnObjectError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+}
+
+/// Converts a gRPC DespawnObjectRequest into an internal request, validating the object ID.
+pub fn despawn_object_request_to_internal_request(
+    despawn_object_request: DespawnObjectRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::DespawnObjectRequest, DespawnObjectError> {
+    let DespawnObjectRequest { object_id } = despawn_object_request;
+
+    trace!("Received request to despawn object {:?}", object_id);
+
+    let object_id = object_id.ok_or(DespawnObjectError::InvalidObjectId)?;
+    let uuid = object_id.uuid.ok_or(DespawnObjectError::InvalidObjectId)?;
+
+    let internal_request = request::object::DespawnObjectRequest {
+        object_id: request::object::ObjectId {
+            uuid: uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| DespawnObjectError::InvalidObjectId)?,
+        },
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectParentError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid parent ID")]
+    InvalidParentId,
+}
+
+/// Converts a gRPC SetObjectParentRequest into an internal request, validating the object and parent IDs.
+pub fn set_parent_request_to_internal_request(
+    set_parent_request: SetObjectParentRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::SetObjectParentRequest, SetObjectParentError> {
+    let SetObjectParentRequest {
+        object_id,
+        parent,
+        keep_world_transform,
+    } = set_parent_request;
+
+    trace!(
+        "Received request to attach object {:?} to {:?}, keeping the world transform: {}",
+        object_id, parent, keep_world_transform
+    );
+
+    let object_id = object_id
+        .and_then(normalize_object_id)
+        .ok_or(SetObjectParentError::InvalidObjectId)?;
+    let parent = parent
+        .map(|parent| normalize_object_id(parent).ok_or(SetObjectParentError::InvalidParentId))
+        .transpose()?;
+
+    let internal_request = request::object::SetObjectParentRequest {
+        object_id,
+        parent,
+        keep_world_transform,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum SetInterpolationError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid interpolation mode")]
+    InvalidMode,
+}
+
+/// Converts a gRPC SetInterpolationRequest into an internal request, targeting every object if no ID is given.
+pub fn set_interpolation_request_to_internal_request(
+    set_interpolation_request: SetInterpolationRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::motion::SetInterpolationRequest, SetInterpolationError> {
+    let SetInterpolationRequest { object_id, mode } = set_interpolation_request;
+
+    trace!(
+        "Received request to set the interpolation mode of {:?} to {:?}",
+        object_id, mode
+    );
+
+    let object_id = object_id
+        .map(|object_id| {
+            normalize_object_id(object_id).ok_or(SetInterpolationError::InvalidObjectId)
+        })
+        .transpose()?;
+    let mode = mode
+        .map(|mode| normalize_interpolation_mode(mode).ok_or(SetInterpolationError::InvalidMode))
+        .transpose()?;
+
+    let internal_request = request::motion::SetInterpolationRequest {
+        object_id,
+        mode,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Transforms a gRPC InterpolationMode into the internal mode, filling in the documented defaults.
+pub fn normalize_interpolation_mode(
+    interpolation_mode: InterpolationMode,
+) -> Option<request::motion::InterpolationMode> {
+    use request::motion::InterpolationMode as Internal;
+
+    let mode = match interpolation_mode.mode? {
+        interpolation_mode::Mode::Snap(SnapInterpolation {}) => Internal::Snap,
+        interpolation_mode::Mode::Lerp(LerpInterpolation { speed }) => Internal::Lerp {
+            speed: speed.unwrap_or(10.0),
+        },
+        interpolation_mode::Mode::ExponentialDecay(ExponentialDecayInterpolation {
+            decay_rate,
+        }) => Internal::ExponentialDecay {
+            decay_rate: decay_rate.unwrap_or(10.0),
+        },
+        interpolation_mode::Mode::Linear(LinearInterpolation {
+            speed,
+            angular_speed,
+        }) => Internal::Linear {
+            speed,
+            angular_speed: angular_speed.unwrap_or(std::f32::consts::PI),
+        },
+        interpolation_mode::Mode::Tween(TweenInterpolation { duration, easing }) => {
+            Internal::Tween {
+                duration: std::time::Duration::try_from_secs_f32(duration).ok()?,
+                easing: normalize_easing_function(EasingFunction::try_from(easing).ok()?),
+            }
+        }
+        interpolation_mode::Mode::Extrapolate(ExtrapolateInterpolation {
+            decay_rate,
+            max_duration,
+        }) => Internal::Extrapolate {
+            decay_rate: decay_rate.unwrap_or(10.0),
+            max_duration: std::time::Duration::try_from_secs_f32(max_duration.unwrap_or(1.0))
+                .ok()?,
+        },
+    };
+
+    mode.is_valid().then_some(mode)
+}
+
+/// Maps a gRPC EasingFunction to the matching Bevy easing curve, linear if unspecified.
+pub fn normalize_easing_function(easing_function: EasingFunction) -> EaseFunction {
+    match easing_function {
+        EasingFunction::Unspecified | EasingFunction::Linear => EaseFunction::Linear,
+        EasingFunction::QuadraticIn => EaseFunction::QuadraticIn,
+        EasingFunction::QuadraticOut => EaseFunction::QuadraticOut,
+        EasingFunction::QuadraticInOut => EaseFunction::QuadraticInOut,
+        EasingFunction::CubicIn => EaseFunction::CubicIn,
+        EasingFunction::CubicOut => EaseFunction::CubicOut,
+        EasingFunction::CubicInOut => EaseFunction::CubicInOut,
+        EasingFunction::SineIn => EaseFunction::SineIn,
+        EasingFunction::SineOut => EaseFunction::SineOut,
+        EasingFunction::SineInOut => EaseFunction::SineInOut,
+        EasingFunction::ExponentialIn => EaseFunction::ExponentialIn,
+        EasingFunction::ExponentialOut => EaseFunction::ExponentialOut,
+        EasingFunction::ExponentialInOut => EaseFunction::ExponentialInOut,
+        EasingFunction::SmoothStep => EaseFunction::SmoothStep,
+        EasingFunction::BackOut => EaseFunction::BackOut,
+        EasingFunction::ElasticOut => EaseFunction::ElasticOut,
+        EasingFunction::BounceOut => EaseFunction::BounceOut,
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectAnimationError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid animation")]
+    InvalidAnimation,
+}
+
+/// Converts a gRPC SetObjectAnimationRequest into an internal request, validating the object ID and keyframes.
+pub fn set_animation_request_to_internal_request(
+    set_animation_request: SetObjectAnimationRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::motion::SetObjectAnimationRequest, SetObjectAnimationError> {
+    let SetObjectAnimationRequest {
+        object_id,
+        animation,
+    } = set_animation_request;
+
+    trace!(
+        "Received request to set the animation of object {:?} to {:?}",
+        object_id, animation
+    );
+
+    let object_id = object_id
+        .and_then(normalize_object_id)
+        .ok_or(SetObjectAnimationError::InvalidObjectId)?;
+    let animation = animation
+        .map(|animation| {
+            normalize_object_animation(animation, color_validation)
+                .ok_or(SetObjectAnimationError::InvalidAnimation)
+        })
+        .transpose()?;
+
+    let internal_request = request::motion::SetObjectAnimationRequest {
+        object_id,
+        animation,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Transforms a gRPC ObjectAnimation into the internal animation, played once at normal speed unless set otherwise.
+pub fn normalize_object_animation(
+    object_animation: ObjectAnimation,
+    color_validation: ColorValidation,
+) -> Option<request::motion::ObjectAnimation> {
+    let repeat = object_animation.repeat();
+    let ObjectAnimation {
+        keyframes, speed, ..
+    } = object_animation;
+
+    let keyframes = keyframes
+        .into_iter()
+        .map(|keyframe| normalize_keyframe(keyframe, color_validation))
+        .collect::<Option<Vec<_>>>()?;
+
+    let animation = request::motion::ObjectAnimation {
+        keyframes,
+        repeat: match repeat {
+            AnimationRepeat::Unspecified | AnimationRepeat::Once => {
+                request::motion::AnimationRepeat::Once
+            }
+            AnimationRepeat::Loop => request::motion::AnimationRepeat::Loop,
+            AnimationRepeat::PingPong => request::motion::AnimationRepeat::PingPong,
+        },
+        speed: speed.unwrap_or(1.0),
+    };
+
+    animation.is_valid().then_some(animation)
+}
+
+/// Transforms a gRPC Keyframe into the internal keyframe, failing if any of its values is invalid.
+pub fn normalize_keyframe(
+    keyframe: Keyframe,
+    color_validation: ColorValidation,
+) -> Option<request::motion::Keyframe> {
+    let Keyframe {
+        time,
+        position,
+        rotation,
+        scale,
+        color,
+    } = keyframe;
+
+    let position = match position {
+        Some(position) => Some(normalize_vector3(position)?),
+        None => None,
+    };
+    let rotation = match rotation {
+        Some(rotation) => Some(normalize_rotation(rotation)?),
+        None => None,
+    };
+    let scale = match scale {
+        Some(scale) => Some(normalize_vector3(scale)?),
+        None => None,
+    };
+    let color = match color {
+        Some(color) => Some(normalize_object_color(color, color_validation).ok()?),
+        None => None,
+    };
+
+    Some(request::motion::Keyframe {
+        time,
+        position,
+        rotation,
+        scale,
+        color,
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectLabelError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid label")]
+    InvalidLabel,
+    #[error("Invalid label offset")]
+    InvalidOffset,
+}
+
+/// Converts a gRPC SetObjectLabelRequest into an internal request, placing the label one unit above the object by default.
+pub fn set_label_request_to_internal_request(
+    set_label_request: SetObjectLabelRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::annotation::SetObjectLabelRequest, SetObjectLabelError> {
+    let SetObjectLabelRequest {
+        object_id,
+        label,
+        offset,
+    } = set_label_request;
+
+    trace!(
+        "Received request to set the label of object {:?} to {:?} at offset {:?}",
+        object_id, label, offset
+    );
+
+    let object_id = object_id
+        .and_then(normalize_object_id)
+        .ok_or(SetObjectLabelError::InvalidObjectId)?;
+    let label = label
+        .map(|label| {
+            normalize_annotation_text(label, color_validation)
+                .ok_or(SetObjectLabelError::InvalidLabel)
+        })
+        .transpose()?;
+    let offset = offset
+        .map(|offset| normalize_vector3(offset).ok_or(SetObjectLabelError::InvalidOffset))
+        .transpose()?
+        .unwrap_or(Vec3::Y);
+
+    let internal_request = request::annotation::SetObjectLabelRequest {
+        object_id,
+        label,
+        offset,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum SetAnnotationError {
+    #[error("Invalid annotation ID")]
+    InvalidAnnotationId,
+    #[error("Invalid annotation text")]
+    InvalidText,
+    #[error("Invalid position")]
+    InvalidPosition,
+}
+
+/// Converts a gRPC SetAnnotationRequest into an internal request, assigning a UUID if none was given.
+pub fn set_annotation_request_to_internal_request(
+    set_annotation_request: SetAnnotationRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::annotation::SetAnnotationRequest, SetAnnotationError> {
+    let SetAnnotationRequest {
+        annotation_id,
+        text,
+        position,
+    } = set_annotation_request;
+
+    trace!(
+        "Received request to set annotation {:?} to {:?} at position {:?}",
+        annotation_id, text, position
+    );
+
+    let annotation_id = match annotation_id {
+        Some(annotation_id) => {
+            normalize_annotation_id(annotation_id).ok_or(SetAnnotationError::InvalidAnnotationId)?
+        }
+        None => request::annotation::AnnotationId {
+            uuid: uuid::Uuid::now_v7(),
+        },
+    };
+    let text = text
+        .and_then(|text| normalize_annotation_text(text, color_validation))
+        .ok_or(SetAnnotationError::InvalidText)?;
+    let position = position
+        .and_then(normalize_vector3)
+        .ok_or(SetAnnotationError::InvalidPosition)?;
+
+    let internal_request = request::annotation::SetAnnotationRequest {
+        annotation_id,
+        text,
+        position,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum RemoveAnnotationError {
+    #[error("Invalid annotation ID")]
+    InvalidAnnotationId,
+}
+
+/// Converts a gRPC RemoveAnnotationRequest into an internal request, validating the annotation ID.
+pub fn remove_annotation_request_to_internal_request(
+    remove_annotation_request: RemoveAnnotationRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::annotation::RemoveAnnotationRequest, RemoveAnnotationError> {
+    let RemoveAnnotationRequest { annotation_id } = remove_annotation_request;
+
+    trace!("Received request to remove annotation {:?}", annotation_id);
+
+    let annotation_id = annotation_id
+        .and_then(normalize_annotation_id)
+        .ok_or(RemoveAnnotationError::InvalidAnnotationId)?;
+
+    let internal_request = request::annotation::RemoveAnnotationRequest {
+        annotation_id,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Transforms a gRPC AnnotationText into the internal text, drawing it white at 16 pixels unless set otherwise.
+pub fn normalize_annotation_text(
+    annotation_text: AnnotationText,
+    color_validation: ColorValidation,
+) -> Option<request::annotation::AnnotationText> {
+    let AnnotationText {
+        text,
+        color,
+        font_size,
+    } = annotation_text;
+
+    let color = match color {
+        Some(color) => normalize_object_color(color, color_validation).ok()?,
+        None => bevy::color::Color::WHITE,
+    };
+
+    let annotation_text = request::annotation::AnnotationText {
+        text,
+        color,
+        font_size: font_size.unwrap_or(16.0),
+    };
+
+    annotation_text.is_valid().then_some(annotation_text)
+}
+
+/// Transforms a gRPC AnnotationId into the internal annotation ID, rejecting a missing or malformed UUID.
+pub fn normalize_annotation_id(
+    annotation_id: AnnotationId,
+) -> Option<request::annotation::AnnotationId> {
+    let uuid = annotation_id.uuid?;
+    Some(request::annotation::AnnotationId {
+        uuid: uuid::Uuid::from_slice(uuid.value.as_slice()).ok()?,
+    })
+}
+
+/// Converts an internal AnnotationId into the gRPC AnnotationId.
+pub fn annotation_id_to_proto(annotation_id: &request::annotation::AnnotationId) -> AnnotationId {
+    AnnotationId {
+        uuid: Some(Uuid {
+            value: annotation_id.uuid.as_bytes().to_vec(),
+        }),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectTrailError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid trail")]
+    InvalidTrail,
+}
+
+/// Converts a gRPC SetObjectTrailRequest into an internal request, validating the object ID and trail.
+pub fn set_trail_request_to_internal_request(
+    set_trail_request: SetObjectTrailRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::trail::SetObjectTrailRequest, SetObjectTrailError> {
+    let SetObjectTrailRequest { object_id, trail } = set_trail_request;
+
+    trace!(
+        "Received request to set the trail of object {:?} to {:?}",
+        object_id, trail
+    );
+
+    let object_id = object_id
+        .and_then(normalize_object_id)
+        .ok_or(SetObjectTrailError::InvalidObjectId)?;
+    let trail = trail
+        .map(|trail| {
+            normalize_object_trail(trail, color_validation).ok_or(SetObjectTrailError::InvalidTrail)
+        })
+        .transpose()?;
+
+    let internal_request = request::trail::SetObjectTrailRequest {
+        object_id,
+        trail,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Transforms a gRPC ObjectTrail into the internal trail settings, drawing a white, 2 pixel wide trail of 2 seconds unless set otherwise.
+pub fn normalize_object_trail(
+    object_trail: ObjectTrail,
+    color_validation: ColorValidation,
+) -> Option<request::trail::TrailSettings> {
+    let ObjectTrail {
+        length,
+        width,
+        color,
+    } = object_trail;
+
+    let color = match color {
+        Some(color) => normalize_object_color(color, color_validation).ok()?,
+        None => bevy::color::Color::WHITE,
+    };
+
+    let trail_settings = request::trail::TrailSettings {
+        length: std::time::Duration::try_from_secs_f32(length.unwrap_or(2.0)).ok()?,
+        width: width.unwrap_or(2.0),
+        color,
+    };
+
+    trail_settings.is_valid().then_some(trail_settings)
+}
+
+#[derive(Error, Debug)]
+pub enum SetPrimitiveError {
+    #[error("Invalid primitive ID")]
+    InvalidPrimitiveId,
+    #[error("Invalid primitive shape")]
+    InvalidShape,
+    #[error("Invalid primitive color")]
+    InvalidColor,
+    #[error("Invalid primitive lifetime")]
+    InvalidLifetime,
+}
+
+/// Converts a gRPC SetPrimitiveRequest into an internal request, assigning a UUID if none was given.
+pub fn set_primitive_request_to_internal_request(
+    set_primitive_request: SetPrimitiveRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::primitive::SetPrimitiveRequest, SetPrimitiveError> {
+    let SetPrimitiveRequest {
+        primitive_id,
+        primitive,
+    } = set_primitive_request;
+
+    trace!(
+        "Received request to set primitive {:?} to {:?}",
+        primitive_id, primitive
+    );
+
+    let primitive_id = match primitive_id {
+        Some(primitive_id) => {
+            normalize_primitive_id(primitive_id).ok_or(SetPrimitiveError::InvalidPrimitiveId)?
+        }
+        None => request::primitive::PrimitiveId {
+            uuid: uuid::Uuid::now_v7(),
+        },
+    };
+    let Primitive {
+        shape,
+        color,
+        lifetime,
+    } = primitive.ok_or(SetPrimitiveError::InvalidShape)?;
+
+    let shape = shape
+        .and_then(normalize_primitive_shape)
+        .ok_or(SetPrimitiveError::InvalidShape)?;
+    let color = match color {
+        Some(color) => normalize_object_color(color, color_validation)
+            .map_err(|_| SetPrimitiveError::InvalidColor)?,
+        None => bevy::color::Color::WHITE,
+    };
+    let lifetime = lifetime
+        .map(|lifetime| {
+            std::time::Duration::try_from_secs_f32(lifetime)
+                .ok()
+                .filter(|lifetime| !lifetime.is_zero())
+                .ok_or(SetPrimitiveError::InvalidLifetime)
+        })
+        .transpose()?;
+
+    let internal_request = request::primitive::SetPrimitiveRequest {
+        primitive_id,
+        shape,
+        color,
+        lifetime,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum RemovePrimitiveError {
+    #[error("Invalid primitive ID")]
+    InvalidPrimitiveId,
+}
+
+/// Converts a gRPC RemovePrimitiveRequest into an internal request, validating the primitive ID.
+pub fn remove_primitive_request_to_internal_request(
+    remove_primitive_request: RemovePrimitiveRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::primitive::RemovePrimitiveRequest, RemovePrimitiveError> {
+    let RemovePrimitiveRequest { primitive_id } = remove_primitive_request;
+
+    trace!("Received request to remove primitive {:?}", primitive_id);
+
+    let primitive_id = primitive_id
+        .and_then(normalize_primitive_id)
+        .ok_or(RemovePrimitiveError::InvalidPrimitiveId)?;
+
+    let internal_request = request::primitive::RemovePrimitiveRequest {
+        primitive_id,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Transforms the gRPC shape of a primitive into the internal shape, rejecting polylines with fewer than two points.
+pub fn normalize_primitive_shape(
+    shape: primitive::Shape,
+) -> Option<request::primitive::PrimitiveShape> {
+    let shape = match shape {
+        primitive::Shape::Line(LinePrimitive { start, end }) => {
+            request::primitive::PrimitiveShape::Line {
+                start: normalize_primitive_point(start?)?,
+                end: normalize_primitive_point(end?)?,
+            }
+        }
+        primitive::Shape::Arrow(ArrowPrimitive { start, end }) => {
+            request::primitive::PrimitiveShape::Arrow {
+                start: normalize_primitive_point(start?)?,
+                end: normalize_primitive_point(end?)?,
+            }
+        }
+        primitive::Shape::Polyline(PolylinePrimitive { points }) => {
+            if points.len() < 2 {
+                return None;
+            }
+            request::primitive::PrimitiveShape::Polyline(
+                points
+                    .into_iter()
+                    .map(normalize_primitive_point)
+                    .collect::<Option<_>>()?,
+            )
+        }
+    };
+
+    Some(shape)
+}
+
+/// Transforms a gRPC PrimitivePoint into the internal point, with no offset from the object unless set.
+pub fn normalize_primitive_point(
+    primitive_point: PrimitivePoint,
+) -> Option<request::primitive::PrimitivePoint> {
+    let point = match primitive_point.point? {
+        primitive_point::Point::Position(position) => {
+            request::primitive::PrimitivePoint::Position(normalize_vector3(position)?)
+        }
+        primitive_point::Point::Object(ObjectAnchor { object_id, offset }) => {
+            request::primitive::PrimitivePoint::Object {
+                object_id: normalize_object_id(object_id?)?,
+                offset: match offset {
+                    Some(offset) => normalize_vector3(offset)?,
+                    None => Vec3::ZERO,
+                },
+            }
+        }
+    };
+
+    Some(point)
+}
+
+/// Transforms a gRPC PrimitiveId into the internal primitive ID, rejecting a missing or malformed UUID.
+pub fn normalize_primitive_id(
+    primitive_id: PrimitiveId,
+) -> Option<request::primitive::PrimitiveId> {
+    let uuid = primitive_id.uuid?;
+    Some(request::primitive::PrimitiveId {
+        uuid: uuid::Uuid::from_slice(uuid.value.as_slice()).ok()?,
+    })
+}
+
+/// Converts an internal PrimitiveId into the gRPC PrimitiveId.
+pub fn primitive_id_to_proto(primitive_id: &request::primitive::PrimitiveId) -> PrimitiveId {
+    PrimitiveId {
+        uuid: Some(Uuid {
+            value: primitive_id.uuid.as_bytes().to_vec(),
+        }),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SetObjectPropertiesError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+    #[error("Invalid object color")]
+    InvalidObjectColor,
+    #[error("Invalid object shape")]
+    InvalidObjectShape,
+    #[error("Invalid object dimensions")]
+    InvalidObjectDimensions,
+    #[error("Invalid object size")]
+    InvalidObjectSize,
+    #[error("Invalid object material")]
+    InvalidObjectMaterial,
+}
+
+/// Converts a gRPC SetObjectPropertiesRequest into an internal request, validating the fields that are set.
+pub fn set_properties_request_to_internal_request(
+    set_properties_request: SetObjectPropertiesRequest,
+    completion: Option<Completion>,
+    color_validation: ColorValidation,
+) -> std::result::Result<request::object::SetObjectPropertiesRequest, SetObjectPropertiesError> {
+    let SetObjectPropertiesRequest {
+        object_id,
+        shape,
+        color,
+        size,
+        dimensions,
+        mesh_id,
+        material,
+    } = set_properties_request;
+
+    trace!(
+        "Received request to set object properties {:?} to shape {:?}, color {:?}, size {:?}, dimensions {:?}, mesh ID {:?}, material {:?}",
+        object_id, shape, color, size, dimensions, mesh_id, material
+    );
+
+    let object_id = object_id.ok_or(SetObjectPropertiesError::InvalidObjectId)?;
+    let uuid = object_id
+        .uuid
+        .ok_or(SetObjectPropertiesError::InvalidObjectId)?;
+
+    let color = color
+        .map(|color| {
+            normalize_object_color(color, color_validation)
+                .map_err(|_| SetObjectPropertiesError::InvalidObjectColor)
+        })
+        .transpose()?;
+    let material = material
+        .map(|material| {
+            normalize_object_material(material, color_validation)
+                .ok_or(SetObjectPropertiesError::InvalidObjectMaterial)
+        })
+        .transpose()?;
+    let shape = shape
+        .map(|shape| {
+            normalize_object_shape(shape, mesh_id)
+                .ok_or(SetObjectPropertiesError::InvalidObjectShape)
+        })
+        .transpose()?;
+    let size = size
+        .map(|size| normalize_object_size(size).ok_or(SetObjectPropertiesError::InvalidObjectSize))
+        .transpose()?;
+    // Without a new shape, the dimensions are checked against the current one by the viewer.
+    let dimensions = dimensions
+        .map(|dimensions| {
+            normalize_object_dimensions(dimensions)
+                .filter(|dimensions| {
+                    shape
+                        .as_ref()
+                        .is_none_or(|shape| dimensions.shape() == *shape)
+                })
+                .ok_or(SetObjectPropertiesError::InvalidObjectDimensions)
+        })
+        .transpose()?;
+
+    let internal_request = request::object::SetObjectPropertiesRequest {
+        object_id: request::object::ObjectId {
+            uuid: uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| SetObjectPropertiesError::InvalidObjectId)?,
+        },
+        color,
+        material,
+        shape,
+        size,
+        dimensions,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+#[derive(Error, Debug)]
+pub enum GetObjectError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+}
+
+/// Converts a gRPC GetObjectRequest into an internal query answered through `response`.
+pub fn get_object_request_to_internal_request(
+    get_object_request: GetObjectRequest,
+    response: ResponseSender<Option<ObjectSnapshot>>,
+) -> std::result::Result<request::query::GetObjectRequest, GetObjectError> {
+    let GetObjectRequest { object_id } = get_object_request;
+
+    trace!("Received request to get object {:?}", object_id);
+
+    let object_id = object_id.ok_or(GetObjectError::InvalidObjectId)?;
+    let uuid = object_id.uuid.ok_or(GetObjectError::InvalidObjectId)?;
+
+    let internal_request = request::query::GetObjectRequest {
+        object_id: request::object::ObjectId {
+            uuid: uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| GetObjectError::InvalidObjectId)?,
+        },
+        response,
+    };
+
+    Ok(internal_request)
+}
+
+/// Page size used when a ListObjectsRequest does not specify one.
+pub const DEFAULT_LIST_OBJECTS_PAGE_SIZE: usize = 100;
+/// Largest page size a ListObjectsRequest may ask for.
+pub const MAX_LIST_OBJECTS_PAGE_SIZE: usize = 1000;
+
+#[derive(Error, Debug)]
+pub enum ListObjectsError {
+    #[error("Invalid page token")]
+    InvalidPageToken,
+}
+
+/// Converts a gRPC ListObjectsRequest into an internal query answered through `response`.
+pub fn list_objects_request_to_internal_request(
+    list_objects_request: ListObjectsRequest,
+    response: ResponseSender<ObjectPage>,
+) -> std::result::Result<request::query::ListObjectsRequest, ListObjectsError> {
+    let ListObjectsRequest {
+        page_size,
+        page_token,
+    } = list_objects_request;
+
+    trace!(
+        "Received request to list objects with page size {} and page token {:?}",
+        page_size, page_token
+    );
+
+    let page_size = match page_size as usize {
+        0 => DEFAULT_LIST_OBJECTS_PAGE_SIZE,
+        page_size => page_size.min(MAX_LIST_OBJECTS_PAGE_SIZE),
+    };
+
+    let start_after = if page_token.is_empty() {
+        None
+    } else {
+        Some(request::object::ObjectId {
+            uuid: uuid::Uuid::parse_str(&page_token)
+                .map_err(|_| ListObjectsError::InvalidPageToken)?,
+        })
+    };
+
+    let internal_request = request::query::ListObjectsRequest {
+        page_size,
+        start_after,
+        response,
+    };
+
+    Ok(internal_request)
+}
+
+/// Converts an internal ObjectSnapshot into the gRPC ObjectState.
+pub fn object_snapshot_to_object_state(snapshot: ObjectSnapshot) -> ObjectState {
+    let ObjectSnapshot {
+        object_id,
+        object_properties,
+        transform,
+        target_transform,
+        parent,
+    } = snapshot;
+
+    ObjectState {
+        id: Some(object_id_to_proto(&object_id)),
+        properties: Some(object_properties_to_proto(&object_properties)),
+        transform: Some(transform_to_proto(&transform)),
+        target_transform: Some(transform_to_proto(&target_transform)),
+        parent: parent.as_ref().map(object_id_to_proto),
+    }
+}
+
+/// Transforms a gRPC ObjectId into the internal object ID, rejecting a missing or malformed UUID.
+pub fn normalize_object_id(object_id: ObjectId) -> Option<request::object::ObjectId> {
+    let uuid = object_id.uuid?;
+    Some(request::object::ObjectId {
+        uuid: uuid::Uuid::from_slice(uuid.value.as_slice()).ok()?,
+    })
+}
+
+/// Converts an internal ObjectId into the gRPC ObjectId.
+pub fn object_id_to_proto(object_id: &request::object::ObjectId) -> ObjectId {
+    ObjectId {
+        uuid: Some(Uuid {
+            value: object_id.uuid.as_bytes().to_vec(),
+        }),
+    }
+}
+
+/// Converts internal ObjectProperties into the gRPC ObjectProperties, reporting the color as RGBA.
+pub fn object_properties_to_proto(
+    object_properties: &request::object::ObjectProperties,
+) -> ObjectProperties {
+    let color = object_properties.color.to_srgba();
+
+    let mesh_id = match &object_properties.shape {
+        request::object::ObjectShape::Mesh(mesh_id) => Some(mesh_id_to_proto(mesh_id)),
+        _ => None,
+    };
+
+    ObjectProperties {
+        shape: match object_properties.shape {
+            request::object::ObjectShape::Cube => ObjectShape::Cube,
+            request::object::ObjectShape::Sphere => ObjectShape::Sphere,
+            request::object::ObjectShape::Cylinder => ObjectShape::Cylinder,
+            request::object::ObjectShape::Capsule => ObjectShape::Capsule,
+            request::object::ObjectShape::Cone => ObjectShape::Cone,
+            request::object::ObjectShape::Torus => ObjectShape::Torus,
+            request::object::ObjectShape::Plane => ObjectShape::Plane,
+            request::object::ObjectShape::Tetrahedron => ObjectShape::Tetrahedron,
+            request::object::ObjectShape::Mesh(_) => ObjectShape::Mesh,
+        }
+        .into(),
+        color: Some(ObjectColor {
+            color: Some(object_color::Color::ColorRgba(Rgba {
+                r: color.red,
+                g: color.green,
+                b: color.blue,
+                a: color.alpha,
+            })),
+        }),
+        size: Some(object_size_to_proto(&object_properties.size)),
+        dimensions: object_properties
+            .dimensions
+            .as_ref()
+            .map(object_dimensions_to_proto),
+        mesh_id,
+        material: Some(object_material_to_proto(&object_properties.material)),
+    }
+}
+
+/// Converts an internal ObjectMaterial into the gRPC ObjectMaterial, reporting the emissive color as RGBA.
+pub fn object_material_to_proto(material: &request::object::ObjectMaterial) -> ObjectMaterial {
+    use request::object::ObjectAlphaMode as Internal;
+
+    let emissive = material.emissive.to_srgba();
+    let (alpha_mode, alpha_cutoff) = match material.alpha_mode {
+        None => (ObjectAlphaMode::Unspecified, None),
+        Some(Internal::Opaque) => (ObjectAlphaMode::Opaque, None),
+        Some(Internal::Mask(cutoff)) => (ObjectAlphaMode::Mask, Some(cutoff)),
+        Some(Internal::Blend) => (ObjectAlphaMode::Blend, None),
+        Some(Internal::Premultiplied) => (ObjectAlphaMode::Premultiplied, None),
+        Some(Internal::Add) => (ObjectAlphaMode::Add, None),
+        Some(Internal::Multiply) => (ObjectAlphaMode::Multiply, None),
+    };
+
+    ObjectMaterial {
+        metallic: Some(material.metallic),
+        perceptual_roughness: Some(material.perceptual_roughness),
+        emissive: Some(ObjectColor {
+            color: Some(object_color::Color::ColorRgba(Rgba {
+                r: emissive.red,
+                g: emissive.green,
+                b: emissive.blue,
+                a: emissive.alpha,
+            })),
+        }),
+        emissive_strength: Some(material.emissive_strength),
+        unlit: material.unlit,
+        alpha_mode: alpha_mode.into(),
+        alpha_cutoff,
+    }
+}
+
+/// Converts an internal ObjectSize into the gRPC ObjectSize, reporting the largest extent as the value.
+pub fn object_size_to_proto(size: &request::object::ObjectSize) -> ObjectSize {
+    match *size {
+        request::object::ObjectSize::Uniform(value) => ObjectSize {
+            value,
+            extents: None,
+        },
+        request::object::ObjectSize::Extents(extents) => ObjectSize {
+            value: extents.max_element(),
+            extents: Some(Vector3 {
+                x: extents.x,
+                y: extents.y,
+                z: extents.z,
+            }),
+        },
+    }
+}
+
+/// Converts internal ObjectDimensions into the gRPC ObjectDimensions.
+pub fn object_dimensions_to_proto(
+    dimensions: &request::object::ObjectDimensions,
+) -> ObjectDimensions {
+    use request::object::ObjectDimensions as Internal;
+
+    let dimensions = match *dimensions {
+        Internal::Cuboid { size } => object_dimensions::Dimensions::Cuboid(CuboidDimensions {
+            size: Some(Vector3 {
+                x: size.x,
+                y: size.y,
+                z: size.z,
+            }),
+        }),
+        Internal::Sphere { radius } => {
+            object_dimensions::Dimensions::Sphere(SphereDimensions { radius })
+        }
+        Internal::Cylinder { radius, height } => {
+            object_dimensions::Dimensions::Cylinder(CylinderDimensions { radius, height })
+        }
+        Internal::Capsule { radius, length } => {
+            object_dimensions::Dimensions::Capsule(CapsuleDimensions { radius, length })
+        }
+        Internal::Cone { radius, height } => {
+            object_dimensions::Dimensions::Cone(ConeDimensions { radius, height })
+        }
+        Internal::Torus {
+            minor_radius,
+            major_radius,
+        } => object_dimensions::Dimensions::Torus(TorusDimensions {
+            minor_radius,
+            major_radius,
+        }),
+        Internal::Plane { size } => object_dimensions::Dimensions::Plane(PlaneDimensions {
+            size: Some(Vector2 {
+                x: size.x,
+                y: size.y,
+            }),
+        }),
+    };
+
+    ObjectDimensions {
+        dimensions: Some(dimensions),
+    }
+}
+
+/// Converts a Bevy Transform into the gRPC Transform.
+pub fn transform_to_proto(transform: &bevy::transform::components::Transform) -> Transform {
+    Transform {
+        translation: Some(Vector3 {
+            x: transform.translation.x,
+            y: transform.translation.y,
+            z: transform.translation.z,
+        }),
+        rotation: Some(Quaternion {
+            x: transform.rotation.x,
+            y: transform.rotation.y,
+            z: transform.rotation.z,
+            w: transform.rotation.w,
+        }),
+        scale: Some(Vector3 {
+            x: transform.scale.x,
+            y: transform.scale.y,
+            z: transform.scale.z,
+        }),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WatchObjectsError {
+    #[error("Invalid object ID")]
+    InvalidObjectId,
+}
+
+/// Converts a gRPC WatchObjectsRequest into the set of object UUIDs to watch; an empty set watches everything.
+pub fn watch_objects_request_to_filter(
+    watch_objects_request: WatchObjectsRequest,
+) -> std::result::Result<HashSet<uuid::Uuid>, WatchObjectsError> {
+    let WatchObjectsRequest { object_ids } = watch_objects_request;
+
+    trace!("Received request to watch objects {:?}", object_ids);
+
+    object_ids
+        .into_iter()
+        .map(|object_id| {
+            let uuid = object_id.uuid.ok_or(WatchObjectsError::InvalidObjectId)?;
+            uuid::Uuid::from_slice(uuid.value.as_slice())
+                .map_err(|_| WatchObjectsError::InvalidObjectId)
+        })
+        .collect()
+}
+
+/// Converts an applied object change into the gRPC ObjectEvent.
+pub fn object_change_notification_to_object_event(
+    notification: ObjectChangeNotification,
+) -> ObjectEvent {
+    let ObjectChangeNotification {
+        frame,
+        object_changed,
+    } = notification;
+
+    let event = match object_changed.change {
+        ObjectChange::Spawned {
+            object_properties,
+            transform,
+            parent,
+        } => object_event::Event::Spawned(ObjectSpawned {
+            properties: Some(object_properties_to_proto(&object_properties)),
+            transform: Some(transform_to_proto(&transform)),
+            parent: parent.as_ref().map(object_id_to_proto),
+        }),
+        ObjectChange::Despawned => object_event::Event::Despawned(ObjectDespawned {}),
+        ObjectChange::Moved { target_transform } => object_event::Event::Moved(ObjectMoved {
+            target_transform: Some(transform_to_proto(&target_transform)),
+        }),
+        ObjectChange::PropertiesChanged { object_properties } => {
+            object_event::Event::PropertiesChanged(ObjectPropertiesChanged {
+                properties: Some(object_properties_to_proto(&object_properties)),
+            })
+        }
+        ObjectChange::ParentChanged {
+            parent,
+            target_transform,
+        } => object_event::Event::ParentChanged(ObjectParentChanged {
+            parent: parent.as_ref().map(object_id_to_proto),
+            target_transform: Some(transform_to_proto(&target_transform)),
+        }),
+    };
+
+    ObjectEvent {
+        object_id: Some(object_id_to_proto(&object_changed.object_id)),
+        frame,
+        event: Some(event),
+    }
+}
+
+/// Transforms a gRPC ObjectShape value into the internal shape, rejecting unspecified or unknown values.
+///
+/// The mesh ID is only used, and then required, for registered meshes.
+pub fn normalize_object_shape(
+    shape: i32,
+    mesh_id: Option<MeshId>,
+) -> Option<request::object::ObjectShape> {
+    match ObjectShape::try_from(shape) {
+        Ok(ObjectShape::Cube) => Some(request::object::ObjectShape::Cube),
+        Ok(ObjectShape::Sphere) => Some(request::object::ObjectShape::Sphere),
+        Ok(ObjectShape::Cylinder) => Some(request::object::ObjectShape::Cylinder),
+        Ok(ObjectShape::Capsule) => Some(request::object::ObjectShape::Capsule),
+        Ok(ObjectShape::Cone) => Some(request::object::ObjectShape::Cone),
+        Ok(ObjectShape::Torus) => Some(request::object::ObjectShape::Torus),
+        Ok(ObjectShape::Plane) => Some(request::object::ObjectShape::Plane),
+        Ok(ObjectShape::Tetrahedron) => Some(request::object::ObjectShape::Tetrahedron),
+        Ok(ObjectShape::Mesh) => mesh_id
+            .and_then(normalize_mesh_id)
+            .map(request::object::ObjectShape::Mesh),
+        _ => None,
+    }
+}
+
+/// Transforms a gRPC MeshId into the internal mesh ID, rejecting a missing or malformed UUID.
+pub fn normalize_mesh_id(mesh_id: MeshId) -> Option<request::object::MeshId> {
+    let uuid = mesh_id.uuid?;
+    Some(request::object::MeshId {
+        uuid: uuid::Uuid::from_slice(uuid.value.as_slice()).ok()?,
+    })
+}
+
+/// Converts an internal MeshId into the gRPC MeshId.
+pub fn mesh_id_to_proto(mesh_id: &request::object::MeshId) -> MeshId {
+    MeshId {
+        uuid: Some(Uuid {
+            value: mesh_id.uuid.as_bytes().to_vec(),
+        }),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RegisterMeshError {
+    #[error("Invalid mesh ID")]
+    InvalidMeshId,
+    #[error("Missing mesh source")]
+    MissingSource,
+    #[error(transparent)]
+    InvalidMesh(#[from] MeshSourceError),
+}
+
+/// Converts a gRPC RegisterMeshRequest into an internal request, parsing the mesh and assigning a UUID if none was given.
+pub fn register_mesh_request_to_internal_request(
+    register_mesh_request: RegisterMeshRequest,
+    completion: Option<Completion>,
+) -> std::result::Result<request::object::RegisterMeshRequest, RegisterMeshError> {
+    let RegisterMeshRequest { mesh_id, source } = register_mesh_request;
+
+    trace!("Received request to register mesh {:?}", mesh_id);
+
+    let mesh_id = match mesh_id {
+        Some(mesh_id) => normalize_mesh_id(mesh_id).ok_or(RegisterMeshError::InvalidMeshId)?,
+        None => request::object::MeshId {
+            uuid: uuid::Uuid::now_v7(),
+        },
+    };
+
+    let mesh = match source.ok_or(RegisterMeshError::MissingSource)? {
+        register_mesh_request::Source::Gltf(bytes) => mesh::gltf_to_mesh(&bytes)?,
+        register_mesh_request::Source::Obj(obj) => mesh::obj_to_mesh(&obj)?,
+        register_mesh_request::Source::Raw(raw_mesh) => mesh::raw_mesh_to_mesh(raw_mesh)?,
+    };
+
+    let internal_request = request::object::RegisterMeshRequest {
+        mesh_id,
+        mesh: request::object::RegisteredMesh::new(mesh).ok_or(MeshSourceError::Empty)?,
+        completion,
+    };
+
+    Ok(internal_request)
+}
+
+/// Transforms a gRPC ObjectSize into the internal size, preferring the extents and rejecting non-finite or non-positive values.
+pub fn normalize_object_size(size: ObjectSize) -> Option<request::object::ObjectSize> {
+    let ObjectSize { value, extents } = size;
+
+    let size = match extents {
+        Some(extents) => request::object::ObjectSize::Extents(normalize_vector3(extents)?),
+        None => request::object::ObjectSize::Uniform(value),
+    };
+
+    size.is_valid().then_some(size)
+}
+
+/// Transforms a gRPC ObjectMaterial into the internal material, filling in defaults and rejecting out-of-range values.
+pub fn normalize_object_material(
+    material: ObjectMaterial,
+    color_validation: ColorValidation,
+) -> Option<request::object::ObjectMaterial> {
+    use request::object::ObjectAlphaMode as Internal;
+
+    let ObjectMaterial {
+        metallic,
+        perceptual_roughness,
+        emissive,
+        emissive_strength,
+        unlit,
+        alpha_mode,
+        alpha_cutoff,
+    } = material;
+
+    let default = request::object::ObjectMaterial::default();
+
+    let emissive = match emissive {
+        Some(emissive) => normalize_object_color(emissive, color_validation).ok()?,
+        None => default.emissive,
+    };
+
+    let alpha_mode = match ObjectAlphaMode::try_from(alpha_mode).ok()? {
+        ObjectAlphaMode::Unspecified => None,
+        ObjectAlphaMode::Opaque => Some(Internal::Opaque),
+        ObjectAlphaMode::Mask => Some(Internal::Mask(alpha_cutoff.unwrap_or(0.5))),
+        ObjectAlphaMode::Blend => Some(Internal::Blend),
+        ObjectAlphaMode::Premultiplied => Some(Internal::Premultiplied),
+        ObjectAlphaMode::Add => Some(Internal::Add),
+        ObjectAlphaMode::Multiply => Some(Internal::Multiply),
+    };
+
+    let material = request::object::ObjectMaterial {
+        metallic: metallic.unwrap_or(default.metallic),
+        perceptual_roughness: perceptual_roughness.unwrap_or(default.perceptual_roughness),
+        emissive,
+        emissive_strength: emissive_strength.unwrap_or(default.emissive_strength),
+        unlit,
+        alpha_mode,
+    };
+
+    material.is_valid().then_some(material)
+}
+
+/// Transforms gRPC ObjectDimensions into the internal dimensions, rejecting missing, non-finite or non-positive lengths.
+pub fn normalize_object_dimensions(
+    dimensions: ObjectDimensions,
+) -> Option<request::object::ObjectDimensions> {
+    use request::object::ObjectDimensions as Internal;
+
+    let dimensions = match dimensions.dimensions? {
+        object_dimensions::Dimensions::Cuboid(CuboidDimensions { size }) => Internal::Cuboid {
+            size: normalize_vector3(size?)?,
+        },
+        object_dimensions::Dimensions::Sphere(SphereDimensions { radius }) => {
+            Internal::Sphere { radius }
+        }
+        object_dimensions::Dimensions::Cylinder(CylinderDimensions { radius, height }) => {
+            Internal::Cylinder { radius, height }
+        }
+        object_dimensions::Dimensions::Capsule(CapsuleDimensions { radius, length }) => {
+            Internal::Capsule { radius, length }
+        }
+        object_dimensions::Dimensions::Cone(ConeDimensions { radius, height }) => {
+            Internal::Cone { radius, height }
+        }
+        object_dimensions::Dimensions::Torus(TorusDimensions {
+            minor_radius,
+            major_radius,
+        }) => Internal::Torus {
+            minor_radius,
+            major_radius,
+        },
+        object_dimensions::Dimensions::Plane(PlaneDimensions { size }) => {
+            let size = size?;
+            Internal::Plane {
+                size: Vec2::new(size.x, size.y),
+            }
+        }
+    };
+
+    dimensions.is_valid().then_some(dimensions)
+}
+
+/// Transforms a gRPC ObjectColor into a Bevy Color, validating values, names and enum variants.
+pub fn normalize_object_color(
+    object_color: ObjectColor,
+    color_validation: ColorValidation,
+) -> anyhow::Result<bevy::color::Color> {
+    let ObjectColor { color } = object_color;
+
+    let color = color.ok_or_else(|| anyhow::anyhow!("Object color is None"))?;
+
+    let bevy_color = match color {
+        object_color::Color::ColorEnum(n) => {
+            let color = ObjectColorEnum::try_from(n)?;
+            match color {
+                ObjectColorEnum::Blue => bevy::color::Color::srgb(0.0, 0.0, 1.0),
+                ObjectColorEnum::Green => bevy::color::Color::srgb(0.0, 1.0, 0.0),
+                ObjectColorEnum::Red => bevy::color::Color::srgb(1.0, 0.0, 0.0),
+                ObjectColorEnum::Unspecified => match color_validation {
+                    ColorValidation::Lenient => {
+                        warn!("Object color is unspecified");
+                        bevy::color::Color::srgb(1.0, 1.0, 1.0)
+                    }
+                    ColorValidation::Strict => {
+                        return Err(anyhow::anyhow!("Object color is unspecified"));
+                    }
+                },
+            }
+        }
+        object_color::Color::ColorRgba(rgba) => {
+            trace!("Object color is RGBA: {:?}", rgba);
+            if (rgba.r > 1.0 || rgba.g > 1.0 || rgba.b > 1.0 || rgba.a > 1.0)
+                || (rgba.r < 0.0 || rgba.g < 0.0 || rgba.b < 0.0 || rgba.a < 0.0)
+            {
+                return Err(anyhow::anyhow!(
+                    "Object color rgba values must be between 0.0 and 1.0"
+                ));
+            }
+
+            bevy::color::Color::srgba(rgba.r, rgba.g, rgba.b, rgba.a)
+        }
+        object_color::Color::ColorName(name) => {
+            trace!("Object color is named: {:?}", name);
+            palette::named_color(&name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown object color name {:?}", name))?
+                .into()
+        }
+        object_color::Color::ColorHex(hex) => {
+            trace!("Object color is hex: {:?}", hex);
+            bevy::color::Srgba::hex(&hex)?.into()
+        }
+        object_color::Color::ColorHsva(hsva) => {
+            trace!("Object color is HSVA: {:?}", hsva);
+            let Hsva {
+                hue,
+                saturation,
+                value,
+                alpha,
+            } = hsva;
+            if !hue.is_finite() || !in_unit_range(&[saturation, value, alpha]) {
+                return Err(anyhow::anyhow!(
+                    "Object color hsva values must be between 0.0 and 1.0, with a finite hue"
+                ));
+            }
+
+            bevy::color::Hsva::new(hue.rem_euclid(360.0), saturation, value, alpha).into()
+        }
+        object_color::Color::ColorHsla(hsla) => {
+            trace!("Object color is HSLA: {:?}", hsla);
+            let Hsla {
+                hue,
+                saturation,
+                lightness,
+                alpha,
+            } = hsla;
+            if !hue.is_finite() || !in_unit_range(&[saturation, lightness, alpha]) {
+                return Err(anyhow::anyhow!(
+                    "Object color hsla values must be between 0.0 and 1.0, with a finite hue"
+                ));
+            }
+
+            bevy::color::Hsla::new(hue.rem_euclid(360.0), saturation, lightness, alpha).into()
+        }
+    };
+
+    Ok(bevy_color)
+}
+
+/// Returns `true` if every value lies within `0..=1`.
+fn in_unit_range(values: &[f32]) -> bool {
+    values.iter().all(|value| (0.0..=1.0).contains(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::color::{Color, Srgba};
+
+    fn normalize(color: object_color::Color, validation: ColorValidation) -> Option<Color> {
+        normalize_object_color(ObjectColor { color: Some(color) }, validation).ok()
+    }
+
+    fn lenient(color: object_color::Color) -> Option<Color> {
+        normalize(color, ColorValidation::Lenient)
+    }
+
+    fn hex(hex: &str) -> object_color::Color {
+        object_color::Color::ColorHex(hex.to_string())
+    }
+
+    #[test]
+    fn missing_colors_are_rejected() {
+        assert!(
+            normalize_object_color(ObjectColor { color: None }, ColorValidation::Lenient).is_err()
+        );
+    }
+
+    #[test]
+    fn unspecified_color_enum_depends_on_validation() {
+        let unspecified = object_color::Color::ColorEnum(ObjectColorEnum::Unspecified as i32);
+
+        assert_eq!(
+            normalize(unspecified.clone(), ColorValidation::Lenient),
+            Some(Color::srgb(1.0, 1.0, 1.0))
+        );
+        assert_eq!(normalize(unspecified, ColorValidation::Strict), None);
+        assert_eq!(
+            normalize(
+                object_color::Color::ColorEnum(ObjectColorEnum::Red as i32),
+                ColorValidation::Strict
+            ),
+            Some(Color::srgb(1.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn unknown_color_enum_values_are_rejected_in_both_modes() {
+        let unknown = object_color::Color::ColorEnum(i32::MAX);
+
+        assert_eq!(normalize(unknown.clone(), ColorValidation::Lenient), None);
+        assert_eq!(normalize(unknown, ColorValidation::Strict), None);
+    }
+
+    #[test]
+    fn rgba_values_must_be_in_unit_range() {
+        let rgba = |r, g, b, a| object_color::Color::ColorRgba(Rgba { r, g, b, a });
+
+        assert_eq!(
+            lenient(rgba(0.0, 0.5, 1.0, 1.0)),
+            Some(Color::srgba(0.0, 0.5, 1.0, 1.0))
+        );
+        assert_eq!(lenient(rgba(1.5, 0.0, 0.0, 1.0)), None);
+        assert_eq!(lenient(rgba(0.0, 0.0, 0.0, -0.1)), None);
+    }
+
+    #[test]
+    fn color_names_use_the_palette() {
+        assert_eq!(
+            lenient(object_color::Color::ColorName("Sky 500".to_string())),
+            Some(bevy::color::palettes::tailwind::SKY_500.into())
+        );
+        assert_eq!(
+            lenient(object_color::Color::ColorName("no such color".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn hex_colors_accept_short_and_long_forms() {
+        let orange = Some(Color::from(Srgba::rgb_u8(0xff, 0x88, 0x00)));
+
+        assert_eq!(lenient(hex("#ff8800")), orange);
+        assert_eq!(lenient(hex("ff8800")), orange);
+        assert_eq!(lenient(hex("#f80")), orange);
+        assert_eq!(
+            lenient(hex("#ff880080")),
+            Some(Srgba::rgba_u8(0xff, 0x88, 0x00, 0x80).into())
+        );
+        assert_eq!(
+            lenient(hex("#f808")),
+            Some(Srgba::rgba_u8(0xff, 0x88, 0x00, 0x88).into())
+        );
+    }
+
+    #[test]
+    fn malformed_hex_colors_are_rejected() {
+        for malformed in ["", "#", "#ff888", "#ff88000", "#gg8800", "#ff8800ff00"] {
+            assert_eq!(lenient(hex(malformed)), None, "{malformed:?}");
+        }
+    }
+
+    #[test]
+    fn hsva_hue_wraps_and_other_values_must_be_in_unit_range() {
+        let hsva = |hue, saturation, value, alpha| {
+            object_color::Color::ColorHsva(Hsva {
+                hue,
+                saturation,
+                value,
+                alpha,
+            })
+        };
+
+        assert_eq!(
+            lenient(hsva(480.0, 1.0, 1.0, 1.0)),
+            Some(bevy::color::Hsva::new(120.0, 1.0, 1.0, 1.0).into())
+        );
+        assert_eq!(
+            lenient(hsva(-90.0, 0.5, 0.5, 1.0)),
+            Some(bevy::color::Hsva::new(270.0, 0.5, 0.5, 1.0).into())
+        );
+        assert_eq!(lenient(hsva(f32::NAN, 1.0, 1.0, 1.0)), None);
+        assert_eq!(lenient(hsva(0.0, 1.1, 1.0, 1.0)), None);
+        assert_eq!(lenient(hsva(0.0, 1.0, 1.0, -1.0)), None);
+    }
+
+    #[test]
+    fn hsla_hue_wraps_and_other_values_must_be_in_unit_range() {
+        let hsla = |hue, saturation, lightness, alpha| {
+            object_color::Color::ColorHsla(Hsla {
+                hue,
+                saturation,
+                lightness,
+                alpha,
+            })
+        };
+
+        assert_eq!(
+            lenient(hsla(720.0, 1.0, 0.5, 1.0)),
+            Some(bevy::color::Hsla::new(0.0, 1.0, 0.5, 1.0).into())
+        );
+        assert_eq!(lenient(hsla(f32::INFINITY, 1.0, 0.5, 1.0)), None);
+        assert_eq!(lenient(hsla(0.0, 1.0, 1.5, 1.0)), None);
+    }
+
+    #[test]
+    fn sequence_longer_than_queue_capacity_is_invalid_argument() {
+        let requests = vec![DespawnObjectRequest::default(); InternalRequestQueue::CAPACITY + 1];
+
+        let error = enqueue_object_request_sequence(
+            requests,
+            despawn_object_request_to_internal_request,
+            ObjectRequest::Despawn,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ObjectRequestSequenceError::TooLong {
+                len,
+                limit: InternalRequestQueue::CAPACITY,
+            } if len == InternalRequestQueue::CAPACITY + 1
+        ));
+        assert_eq!(
+            tonic::Status::from(error).code(),
+            tonic::Code::InvalidArgument
+        );
+        assert!(INTERNAL_REQUEST_QUEUE.is_empty());
+    }
+
+    #[test]
+    fn object_size_rejects_non_positive_extents() {
+        let extents = |x, y, z| ObjectSize {
+            value: 1.0,
+            extents: Some(Vector3 { x, y, z }),
+        };
+
+        assert!(normalize_object_size(extents(1.0, 2.0, 3.0)).is_some());
+        assert!(normalize_object_size(extents(1.0, 0.0, 3.0)).is_none());
+        assert!(normalize_object_size(extents(-1.0, 2.0, 3.0)).is_none());
+        assert!(normalize_object_size(extents(1.0, f32::NAN, 3.0)).is_none());
+        assert!(
+            normalize_object_size(ObjectSize {
+                value: 0.0,
+                extents: None,
+            })
+            .is_none()
+        );
+    }
 }
@@ -2,8 +2,11 @@
 
 use clap::Parser;
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use bevy::prelude::*;
+use grpc::viewer_rpc::service::ColorValidation;
+use viewer::manage_objects::request::motion::SimulationClock;
 
 #[derive(Parser)]
 #[command(author, version, about)]
@@ -12,13 +15,34 @@ struct Cli {
     /// It should be in the format of "IP:Port".
     #[arg(long, default_value = "127.0.0.1:50051", name = "grpc-addr")]
     grpc_addr: SocketAddr,
+
+    /// Rejects requests whose color is OBJECT_COLOR_ENUM_UNSPECIFIED instead of drawing the object white.
+    #[arg(long, name = "strict-colors")]
+    strict_colors: bool,
+
+    /// How far behind the newest timestamped update the scene is drawn, in seconds.
+    #[arg(long, default_value = "0.1", name = "interpolation-delay", value_parser = parse_seconds)]
+    interpolation_delay: Duration,
+}
+
+/// Parses a non-negative number of seconds.
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{e}"))
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    let color_validation = if cli.strict_colors {
+        ColorValidation::Strict
+    } else {
+        ColorValidation::Lenient
+    };
+
     App::new()
-        .insert_resource(grpc::GrpcServer::new(cli.grpc_addr))
+        .insert_resource(grpc::GrpcServer::new(cli.grpc_addr, color_validation))
+        .insert_resource(SimulationClock::new(cli.interpolation_delay))
         .add_plugins(grpc::RpcPlugin)
         .add_plugins(viewer::ViewerPlugin)
         .run();
@@ -1,4 +1,5 @@
 use bevy::{
+    core_pipeline::bloom::Bloom,
     input::mouse::{AccumulatedMouseMotion, MouseWheel},
     prelude::*,
 };
@@ -58,6 +59,12 @@ fn setup_camera(mut commands: Commands) {
     commands.spawn((
         Name::new("Main Camera"),
         Camera3d::default(),
+        // HDR with bloom lets emissive objects glow.
+        Camera {
+            hdr: true,
+            ..default()
+        },
+        Bloom::NATURAL,
         Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
     ));
 }
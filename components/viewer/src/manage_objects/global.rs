@@ -1,30 +1,82 @@
 use std::ops::Deref;
 
 use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
 
-use crate::types::ThreadSafeVecRw;
+use crate::types::BoundedQueue;
 
-pub static INTERNAL_REQUEST_LIST: Lazy<InternalRequestList> = Lazy::new(InternalRequestList::new);
+pub static INTERNAL_REQUEST_QUEUE: Lazy<InternalRequestQueue> =
+    Lazy::new(InternalRequestQueue::new);
+
+pub static OBJECT_CHANGE_BROADCAST: Lazy<ObjectChangeBroadcast> =
+    Lazy::new(ObjectChangeBroadcast::new);
 
 #[derive(Debug)]
-pub struct InternalRequestList {
-    list: ThreadSafeVecRw<super::request::InternalRequest>,
+pub struct InternalRequestQueue {
+    queue: BoundedQueue<super::request::InternalRequest>,
 }
 
-impl InternalRequestList {
-    /// Creates a new internal request list backed by a thread-safe vector.
+impl InternalRequestQueue {
+    /// Maximum number of requests waiting to be processed by the Bevy world.
+    pub const CAPACITY: usize = 65536;
+
+    /// Creates a new internal request queue backed by a bounded channel.
     pub fn new() -> Self {
-        InternalRequestList {
-            list: ThreadSafeVecRw::new(),
+        InternalRequestQueue {
+            queue: BoundedQueue::new(Self::CAPACITY),
         }
     }
 }
 
-impl Deref for InternalRequestList {
-    type Target = ThreadSafeVecRw<super::request::InternalRequest>;
+impl Default for InternalRequestQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for InternalRequestQueue {
+    type Target = BoundedQueue<super::request::InternalRequest>;
+
+    /// Returns a reference to the underlying bounded request queue.
+    fn deref(&self) -> &Self::Target {
+        &self.queue
+    }
+}
+
+/// An applied object change together with the frame it was applied in.
+#[derive(Debug, Clone)]
+pub struct ObjectChangeNotification {
+    pub frame: u32,
+    pub object_changed: super::request::object::ObjectChanged,
+}
+
+#[derive(Debug)]
+pub struct ObjectChangeBroadcast {
+    sender: broadcast::Sender<ObjectChangeNotification>,
+}
+
+impl ObjectChangeBroadcast {
+    /// Number of notifications a watcher may fall behind before it starts missing them.
+    pub const CAPACITY: usize = 65536;
+
+    /// Creates a new broadcast channel with no subscribers.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(Self::CAPACITY);
+        ObjectChangeBroadcast { sender }
+    }
+}
+
+impl Default for ObjectChangeBroadcast {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for ObjectChangeBroadcast {
+    type Target = broadcast::Sender<ObjectChangeNotification>;
 
-    /// Returns a reference to the underlying thread-safe request vector.
+    /// Returns a reference to the underlying broadcast sender.
     fn deref(&self) -> &Self::Target {
-        &self.list
+        &self.sender
     }
 }
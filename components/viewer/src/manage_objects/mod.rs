@@ -7,9 +7,8 @@ use bevy::prelude::*;
 pub struct ManageObjectsPlugin;
 
 impl Plugin for ManageObjectsPlugin {
-    /// Inserts the InternalRequestCursor resource and adds the InternalRequestPlugin.
+    /// Adds the InternalRequestPlugin.
     fn build(&self, app: &mut App) {
-        app.insert_resource(request::InternalRequestCursor::new())
-            .add_plugins(request::InternalRequestPlugin);
+        app.add_plugins(request::InternalRequestPlugin);
     }
 }
@@ -1,75 +1,78 @@
+pub mod annotation;
+pub mod batch;
+pub mod motion;
 pub mod object;
+pub mod primitive;
+pub mod query;
+pub mod trail;
 
 use bevy::prelude::*;
 
-use super::global::INTERNAL_REQUEST_LIST;
+use super::global::INTERNAL_REQUEST_QUEUE;
 
 pub struct InternalRequestPlugin;
 
 impl Plugin for InternalRequestPlugin {
-    /// Registers the object request plugin and the request-processing system.
+    /// Registers the plugin of every kind of request and the request-processing system.
     fn build(&self, app: &mut App) {
         app.add_plugins(object::ObjectRequestPlugin)
+            .add_plugins(motion::MotionRequestPlugin)
+            .add_plugins(query::QueryRequestPlugin)
+            .add_plugins(annotation::AnnotationRequestPlugin)
+            .add_plugins(primitive::PrimitiveRequestPlugin)
+            .add_plugins(trail::TrailRequestPlugin)
+            .add_plugins(batch::BatchRequestPlugin)
             .add_systems(Update, process_requests);
     }
 }
 
-/// Processes queued internal requests and emits corresponding spawn/position events.
+/// Drains the internal request queue and emits the event corresponding to each request.
 pub fn process_requests(
-    mut request_cursor: ResMut<InternalRequestCursor>,
-    mut spawn_request_event: EventWriter<object::SpawnObjectRequest>,
-    mut set_position_request_event: EventWriter<object::SetObjectPositionRequest>,
+    mut object_request_writers: object::ObjectRequestWriters,
+    mut motion_request_writers: motion::MotionRequestWriters,
+    mut query_request_writers: query::QueryRequestWriters,
+    mut annotation_request_writers: annotation::AnnotationRequestWriters,
+    mut primitive_request_writers: primitive::PrimitiveRequestWriters,
+    mut trail_request_writers: trail::TrailRequestWriters,
+    mut batch_request_writers: batch::BatchRequestWriters,
 ) {
-    let reader = INTERNAL_REQUEST_LIST.get_reader();
-    if reader.is_err() {
-        error!("Failed to get reader for request queue");
-        return;
-    }
-    let reader = reader.unwrap();
+    // Only take what is queued right now so that a steady stream of requests can't stall the frame.
+    let requests = INTERNAL_REQUEST_QUEUE.drain(INTERNAL_REQUEST_QUEUE.len());
 
-    // Process the requests in the queue
-    for request in reader[request_cursor.current_position..].iter() {
+    for request in requests {
         match request {
-            InternalRequest::ObjectRequest(object_request) => match object_request {
-                object::ObjectRequest::Spawn(spawn_request) => {
-                    spawn_request_event.write(spawn_request.clone());
-                }
-                object::ObjectRequest::SetPosition(set_position_request) => {
-                    set_position_request_event.write(set_position_request.clone());
-                }
-            },
+            InternalRequest::ObjectRequest(object_request) => {
+                object_request_writers.write(object_request);
+            }
+            InternalRequest::MotionRequest(motion_request) => {
+                motion_request_writers.write(motion_request);
+            }
+            InternalRequest::QueryRequest(query_request) => {
+                query_request_writers.write(query_request);
+            }
+            InternalRequest::AnnotationRequest(annotation_request) => {
+                annotation_request_writers.write(annotation_request);
+            }
+            InternalRequest::PrimitiveRequest(primitive_request) => {
+                primitive_request_writers.write(primitive_request);
+            }
+            InternalRequest::TrailRequest(trail_request) => {
+                trail_request_writers.write(trail_request);
+            }
+            InternalRequest::BatchRequest(batch_request) => {
+                batch_request_writers.write(batch_request);
+            }
         }
-
-        request_cursor.increment();
     }
 }
 
 #[derive(Debug)]
 pub enum InternalRequest {
     ObjectRequest(object::ObjectRequest),
-}
-
-#[derive(Debug, Resource)]
-pub struct InternalRequestCursor {
-    pub current_position: usize,
-}
-
-impl InternalRequestCursor {
-    pub fn new() -> Self {
-        InternalRequestCursor {
-            current_position: 0,
-        }
-    }
-
-    pub fn reset(&mut self) {
-        self.current_position = 0;
-    }
-
-    pub fn increment(&mut self) {
-        self.current_position += 1;
-    }
-
-    pub fn set(&mut self, position: usize) {
-        self.current_position = position;
-    }
+    MotionRequest(motion::MotionRequest),
+    QueryRequest(query::QueryRequest),
+    AnnotationRequest(annotation::AnnotationRequest),
+    PrimitiveRequest(primitive::PrimitiveRequest),
+    TrailRequest(trail::TrailRequest),
+    BatchRequest(batch::BatchRequest),
 }
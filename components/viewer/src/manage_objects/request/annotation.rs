@@ -0,0 +1,390 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use std::collections::HashMap;
+use std::fmt::Display;
+use uuid::Uuid;
+
+use super::object::{
+    Completion, ObjectId, ObjectIndex, ObjectRequest, ObjectRequestError, complete,
+};
+
+pub struct AnnotationRequestPlugin;
+
+impl Plugin for AnnotationRequestPlugin {
+    /// Registers annotation request events, their handler and the system placing text on screen.
+    ///
+    /// Requests share one event so that they are handled in the order they arrived, e.g. an
+    /// annotation removed and set again in the same frame is shown.
+    ///
+    /// Text is placed after transforms have been propagated, so it follows the objects as they
+    /// were drawn this frame.
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnnotationIndex>()
+            .init_resource::<LabelIndex>()
+            .add_event::<AnnotationRequest>()
+            .add_systems(
+                Update,
+                AnnotationRequest::event_handler
+                    .after(super::process_requests)
+                    // Objects spawned in the same frame can be labeled right away.
+                    .after(ObjectRequest::event_handler),
+            )
+            .add_systems(
+                PostUpdate,
+                (remove_orphaned_labels, place_world_text)
+                    .chain()
+                    .after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Requests that show text in the scene.
+#[derive(Debug, Clone, Event)]
+pub enum AnnotationRequest {
+    SetObjectLabel(SetObjectLabelRequest),
+    SetAnnotation(SetAnnotationRequest),
+    RemoveAnnotation(RemoveAnnotationRequest),
+}
+
+impl AnnotationRequest {
+    /// Handles annotation events one by one, in the order they were sent.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        object_index: Res<ObjectIndex>,
+        mut label_index: ResMut<LabelIndex>,
+        mut annotation_index: ResMut<AnnotationIndex>,
+        mut commands: Commands,
+    ) {
+        for event in event_reader.read() {
+            match event {
+                AnnotationRequest::SetObjectLabel(request) => {
+                    request.apply(&object_index, &mut label_index, &mut commands);
+                }
+                AnnotationRequest::SetAnnotation(request) => {
+                    request.apply(&mut annotation_index, &mut commands);
+                }
+                AnnotationRequest::RemoveAnnotation(request) => {
+                    request.apply(&mut annotation_index, &mut commands);
+                }
+            }
+        }
+    }
+}
+
+/// Event writer for annotation requests.
+#[derive(SystemParam)]
+pub struct AnnotationRequestWriters<'w> {
+    requests: EventWriter<'w, AnnotationRequest>,
+}
+
+impl AnnotationRequestWriters<'_> {
+    /// Emits the given annotation request as an event.
+    pub fn write(&mut self, request: AnnotationRequest) {
+        self.requests.write(request);
+    }
+}
+
+/// Resource mapping annotation IDs to the entities of their text.
+#[derive(Resource, Debug, Default)]
+pub struct AnnotationIndex {
+    entities: HashMap<AnnotationId, Entity>,
+}
+
+impl AnnotationIndex {
+    /// Returns the text entity of the annotation with the given ID, if there is one.
+    pub fn get(&self, annotation_id: &AnnotationId) -> Option<Entity> {
+        self.entities.get(annotation_id).copied()
+    }
+
+    /// Returns the number of annotations in the scene.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if there are no annotations in the scene.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// The text of a label or annotation and how it is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationText {
+    pub text: String,
+    pub color: Color,
+    /// In logical pixels; the text keeps its size however far away its anchor is.
+    pub font_size: f32,
+}
+
+impl AnnotationText {
+    /// Returns `true` if the font size is finite and positive.
+    pub fn is_valid(&self) -> bool {
+        self.font_size.is_finite() && self.font_size > 0.0
+    }
+
+    /// Returns the text components of a UI node showing this text.
+    fn bundle(&self) -> (Text, TextFont, TextColor) {
+        (
+            Text::new(self.text.clone()),
+            TextFont::from_font_size(self.font_size),
+            TextColor(self.color),
+        )
+    }
+}
+
+/// Component of a UI text node that is kept over a point in the world.
+#[derive(Component, Debug, Clone)]
+pub struct WorldText {
+    pub anchor: WorldTextAnchor,
+}
+
+/// The point in the world a [`WorldText`] is kept over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorldTextAnchor {
+    /// Follows an object; the text is removed along with it.
+    Object { entity: Entity, offset: Vec3 },
+    /// Stays at a fixed position.
+    Position(Vec3),
+}
+
+/// Resource mapping object entities to the entities of their labels.
+///
+/// Updated as soon as a label is created or removed, so that several requests for the same
+/// object in one frame never show more than one label.
+#[derive(Resource, Debug, Default)]
+pub struct LabelIndex {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl LabelIndex {
+    /// Returns the label entity of the given object, if it has a label.
+    pub fn get(&self, object: Entity) -> Option<Entity> {
+        self.entities.get(&object).copied()
+    }
+
+    /// Returns the number of labels in the scene.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if there are no labels in the scene.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetObjectLabelRequest {
+    pub object_id: ObjectId,
+    /// The new label; `None` removes the current one.
+    pub label: Option<AnnotationText>,
+    /// Where the label is drawn relative to the object's origin, in world units.
+    pub offset: Vec3,
+    pub completion: Option<Completion>,
+}
+
+impl SetObjectLabelRequest {
+    /// Creates, updates or removes the text shown over the object.
+    fn apply(
+        &self,
+        object_index: &ObjectIndex,
+        label_index: &mut LabelIndex,
+        commands: &mut Commands,
+    ) {
+        let Some(entity) = object_index.get(&self.object_id) else {
+            trace!("Object {} not found; ignoring label", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        trace!(
+            "Setting label of object {} to {:?}",
+            self.object_id, self.label
+        );
+
+        let world_text = WorldText {
+            anchor: WorldTextAnchor::Object {
+                entity,
+                offset: self.offset,
+            },
+        };
+        match (&self.label, label_index.get(entity)) {
+            // Updated in place so that frequently changing readouts don't flicker.
+            (Some(text), Some(label_entity)) => {
+                commands
+                    .entity(label_entity)
+                    .insert((text.bundle(), world_text));
+            }
+            (Some(text), None) => {
+                let label_entity = commands
+                    .spawn((
+                        Name::new(format!("Label of {}", self.object_id)),
+                        text.bundle(),
+                        world_text_node(),
+                        world_text,
+                    ))
+                    .id();
+                label_index.entities.insert(entity, label_entity);
+            }
+            (None, Some(label_entity)) => {
+                commands.entity(label_entity).despawn();
+                label_index.entities.remove(&entity);
+            }
+            (None, None) => {}
+        }
+
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetAnnotationRequest {
+    pub annotation_id: AnnotationId,
+    pub text: AnnotationText,
+    pub position: Vec3,
+    pub completion: Option<Completion>,
+}
+
+impl SetAnnotationRequest {
+    /// Creates free-floating text, or replaces the annotation with the same ID.
+    fn apply(&self, annotation_index: &mut AnnotationIndex, commands: &mut Commands) {
+        trace!(
+            "Setting annotation {} at {:?} to {:?}",
+            self.annotation_id, self.position, self.text
+        );
+
+        let bundle = (
+            self.text.bundle(),
+            WorldText {
+                anchor: WorldTextAnchor::Position(self.position),
+            },
+        );
+        match annotation_index.get(&self.annotation_id) {
+            Some(entity) => {
+                commands.entity(entity).insert(bundle);
+            }
+            None => {
+                let entity = commands
+                    .spawn((
+                        Name::new(self.annotation_id.to_string()),
+                        bundle,
+                        world_text_node(),
+                    ))
+                    .id();
+                annotation_index
+                    .entities
+                    .insert(self.annotation_id.clone(), entity);
+            }
+        }
+
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoveAnnotationRequest {
+    pub annotation_id: AnnotationId,
+    pub completion: Option<Completion>,
+}
+
+impl RemoveAnnotationRequest {
+    /// Despawns the text of the matching annotation.
+    fn apply(&self, annotation_index: &mut AnnotationIndex, commands: &mut Commands) {
+        let Some(entity) = annotation_index.entities.remove(&self.annotation_id) else {
+            trace!(
+                "Annotation {} not found; ignoring removal",
+                self.annotation_id
+            );
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::AnnotationNotFound(
+                    self.annotation_id.clone(),
+                )),
+            );
+            return;
+        };
+
+        trace!("Removing annotation {}", self.annotation_id);
+        commands.entity(entity).despawn();
+        complete(&self.completion, Ok(()));
+    }
+}
+
+/// Returns a UI node that is positioned freely over the scene; it stays hidden until placed.
+fn world_text_node() -> (Node, Visibility) {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Visibility::Hidden,
+    )
+}
+
+/// Removes the labels of despawned objects.
+fn remove_orphaned_labels(
+    mut label_index: ResMut<LabelIndex>,
+    objects: Query<(), With<GlobalTransform>>,
+    mut commands: Commands,
+) {
+    label_index.entities.retain(|&object, &mut label_entity| {
+        let orphaned = !objects.contains(object);
+        if orphaned {
+            commands.entity(label_entity).despawn();
+        }
+        !orphaned
+    });
+}
+
+/// Keeps every world text centered above its anchor as seen from the camera, hiding the text
+/// whose anchor is behind the camera or gone.
+fn place_world_text(
+    camera: Single<(&Camera, &GlobalTransform), With<Camera3d>>,
+    mut texts: Query<(&WorldText, &ComputedNode, &mut Node, &mut Visibility)>,
+    anchors: Query<&GlobalTransform>,
+) {
+    let (camera, camera_transform) = *camera;
+
+    for (world_text, computed_node, mut node, mut visibility) in &mut texts {
+        let world_position = match world_text.anchor {
+            WorldTextAnchor::Object {
+                entity: object,
+                offset,
+            } => match anchors.get(object) {
+                Ok(anchor) => anchor.translation() + offset,
+                Err(_) => {
+                    *visibility = Visibility::Hidden;
+                    continue;
+                }
+            },
+            WorldTextAnchor::Position(position) => position,
+        };
+
+        let Ok(viewport_position) = camera.world_to_viewport(camera_transform, world_position)
+        else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        // The size is that of the last layout, in physical pixels.
+        let size = computed_node.size() * computed_node.inverse_scale_factor();
+        node.left = Val::Px(viewport_position.x - size.x / 2.0);
+        node.top = Val::Px(viewport_position.y - size.y);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct AnnotationId {
+    pub uuid: Uuid,
+}
+
+impl Display for AnnotationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AnnotationId({})", self.uuid)
+    }
+}
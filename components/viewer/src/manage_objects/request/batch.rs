@@ -0,0 +1,464 @@
+use bevy::ecs::system::{SystemParam, SystemState};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::object::{
+    Completion, DespawnObjectRequest, ObjectId, ObjectIndex, ObjectProperties, ObjectRequest,
+    ObjectRequestError, ObjectRequestParams, RegisteredMeshes, SetObjectPropertiesRequest,
+    SetObjectTransformRequest, SpawnObjectRequest, complete,
+};
+
+pub struct BatchRequestPlugin;
+
+impl Plugin for BatchRequestPlugin {
+    /// Registers batch request events and their handler.
+    ///
+    /// Batches are handled after every other object request of the frame, so they are checked
+    /// against the scene those requests left, and their operations are applied right away in
+    /// their own order.
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyBatchRequest>().add_systems(
+            Update,
+            ApplyBatchRequest::event_handler.after(ObjectRequest::event_handler),
+        );
+    }
+}
+
+/// Requests that apply several changes at once.
+#[derive(Debug)]
+pub enum BatchRequest {
+    ApplyBatch(ApplyBatchRequest),
+}
+
+/// Event writers for every kind of batch request.
+#[derive(SystemParam)]
+pub struct BatchRequestWriters<'w> {
+    apply_batch: EventWriter<'w, ApplyBatchRequest>,
+}
+
+impl BatchRequestWriters<'_> {
+    /// Emits the event corresponding to the given batch request.
+    pub fn write(&mut self, request: BatchRequest) {
+        match request {
+            BatchRequest::ApplyBatch(apply_batch_request) => {
+                self.apply_batch.write(apply_batch_request);
+            }
+        }
+    }
+}
+
+/// One change of a batch; the completion of the request it holds is not used.
+#[derive(Debug, Clone)]
+pub enum BatchOperation {
+    Spawn(SpawnObjectRequest),
+    SetTransform(SetObjectTransformRequest),
+    SetProperties(SetObjectPropertiesRequest),
+    Despawn(DespawnObjectRequest),
+}
+
+impl BatchOperation {
+    /// Replaces the completion of the request the operation holds.
+    fn set_completion(&mut self, completion: Completion) {
+        let request_completion = match self {
+            BatchOperation::Spawn(request) => &mut request.completion,
+            BatchOperation::SetTransform(request) => &mut request.completion,
+            BatchOperation::SetProperties(request) => &mut request.completion,
+            BatchOperation::Despawn(request) => &mut request.completion,
+        };
+        *request_completion = Some(completion);
+    }
+}
+
+impl From<BatchOperation> for ObjectRequest {
+    fn from(operation: BatchOperation) -> Self {
+        match operation {
+            BatchOperation::Spawn(request) => ObjectRequest::Spawn(request),
+            BatchOperation::SetTransform(request) => ObjectRequest::SetTransform(request),
+            BatchOperation::SetProperties(request) => ObjectRequest::SetProperties(request),
+            BatchOperation::Despawn(request) => ObjectRequest::Despawn(request),
+        }
+    }
+}
+
+/// The parts of the world a batch is checked against.
+#[derive(SystemParam)]
+pub struct BatchSceneParams<'w, 's> {
+    object_index: Res<'w, ObjectIndex>,
+    registered_meshes: Res<'w, RegisteredMeshes>,
+    properties: Query<'w, 's, &'static ObjectProperties>,
+    children: Query<'w, 's, &'static Children>,
+    object_ids: Query<'w, 's, &'static ObjectId>,
+}
+
+/// The objects of the scene as a batch would leave them, followed operation by operation.
+struct BatchScene<'a, 'w, 's> {
+    params: &'a BatchSceneParams<'w, 's>,
+    /// Objects spawned, changed or despawned by the operations checked so far; `None` for the
+    /// despawned ones.
+    changes: HashMap<ObjectId, Option<ObjectProperties>>,
+    /// Objects spawned by the operations checked so far and not despawned since, with their parent.
+    spawned: HashMap<ObjectId, Option<ObjectId>>,
+}
+
+impl<'a, 'w, 's> BatchScene<'a, 'w, 's> {
+    fn new(params: &'a BatchSceneParams<'w, 's>) -> Self {
+        BatchScene {
+            params,
+            changes: HashMap::new(),
+            spawned: HashMap::new(),
+        }
+    }
+
+    /// Returns the properties of an object, or `None` if it does not exist at this point of the
+    /// batch.
+    fn get(&self, object_id: &ObjectId) -> Option<&ObjectProperties> {
+        match self.changes.get(object_id) {
+            Some(props) => props.as_ref(),
+            None => self
+                .params
+                .object_index
+                .get(object_id)
+                .and_then(|entity| self.params.properties.get(entity).ok()),
+        }
+    }
+
+    /// Applies every operation in order, failing with the index and error of the first one its
+    /// handler would reject.
+    fn check(&mut self, operations: &[BatchOperation]) -> Result<(), ObjectRequestError> {
+        for (index, operation) in operations.iter().enumerate() {
+            self.apply(operation)
+                .map_err(|error| ObjectRequestError::BatchOperation {
+                    index,
+                    error: Box::new(error),
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Applies an operation, failing with the error its handler would report.
+    fn apply(&mut self, operation: &BatchOperation) -> Result<(), ObjectRequestError> {
+        match operation {
+            BatchOperation::Spawn(request) => {
+                if self.get(&request.object_id).is_some() {
+                    return Err(ObjectRequestError::AlreadyExists(request.object_id.clone()));
+                }
+                if let Some(parent) = &request.parent
+                    && self.get(parent).is_none()
+                {
+                    return Err(ObjectRequestError::NotFound(parent.clone()));
+                }
+                request
+                    .object_properties
+                    .check_mesh(&self.params.registered_meshes)?;
+                self.changes.insert(
+                    request.object_id.clone(),
+                    Some(request.object_properties.clone()),
+                );
+                self.spawned
+                    .insert(request.object_id.clone(), request.parent.clone());
+            }
+            BatchOperation::SetTransform(request) => {
+                if self.get(&request.object_id).is_none() {
+                    return Err(ObjectRequestError::NotFound(request.object_id.clone()));
+                }
+            }
+            BatchOperation::SetProperties(request) => {
+                let props = self
+                    .get(&request.object_id)
+                    .ok_or_else(|| ObjectRequestError::NotFound(request.object_id.clone()))?;
+                let new_props = request.updated_properties(props)?;
+                if request.changes_mesh() {
+                    new_props.check_mesh(&self.params.registered_meshes)?;
+                }
+                self.changes
+                    .insert(request.object_id.clone(), Some(new_props));
+            }
+            BatchOperation::Despawn(request) => {
+                if self.get(&request.object_id).is_none() {
+                    return Err(ObjectRequestError::NotFound(request.object_id.clone()));
+                }
+                self.despawn(request.object_id.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Marks an object and its descendants as despawned.
+    fn despawn(&mut self, object_id: ObjectId) {
+        let mut pending = vec![object_id];
+        while let Some(object_id) = pending.pop() {
+            pending.extend(
+                self.spawned
+                    .iter()
+                    .filter(|(_, parent)| parent.as_ref() == Some(&object_id))
+                    .map(|(child_id, _)| child_id.clone()),
+            );
+            // Objects spawned by the batch have no children in the scene yet, even if they reuse
+            // the ID of an object it despawned.
+            if !self.spawned.contains_key(&object_id)
+                && let Some(children) = self
+                    .params
+                    .object_index
+                    .get(&object_id)
+                    .and_then(|entity| self.params.children.get(entity).ok())
+            {
+                pending.extend(
+                    self.params
+                        .object_ids
+                        .iter_many(children)
+                        .filter(|child_id| !self.spawned.contains_key(*child_id))
+                        .cloned(),
+                );
+            }
+
+            self.spawned.remove(&object_id);
+            self.changes.insert(object_id, None);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct ApplyBatchRequest {
+    /// Applied in order, as if they were sent one by one.
+    pub operations: Vec<BatchOperation>,
+    pub completion: Option<Completion>,
+}
+
+impl ApplyBatchRequest {
+    /// Handles batch events by checking every operation against the scene first, then applying
+    /// them one after the other if none of them would fail.
+    ///
+    /// The batch completes once its last operation has been applied.
+    pub fn event_handler(
+        world: &mut World,
+        event_reader: &mut SystemState<EventReader<Self>>,
+        scene_params: &mut SystemState<BatchSceneParams>,
+        params: &mut SystemState<ObjectRequestParams>,
+    ) {
+        let batches: Vec<Self> = event_reader.get_mut(world).read().cloned().collect();
+        for batch in batches {
+            let checked = BatchScene::new(&scene_params.get(world)).check(&batch.operations);
+            if let Err(error) = checked {
+                trace!("Cannot apply batch: {}; ignoring it", error);
+                complete(&batch.completion, Err(error));
+                continue;
+            }
+
+            trace!("Applying batch of {} operations", batch.operations.len());
+            let mut result = Ok(());
+            for (index, mut operation) in batch.operations.into_iter().enumerate() {
+                let (completion, mut receiver) = Completion::channel();
+                operation.set_completion(completion);
+                ObjectRequest::from(operation).apply(&mut params.get_mut(world));
+                params.apply(world);
+
+                // The check above mirrors the handlers, so this only happens if they disagree;
+                // the operations applied so far are kept.
+                if let Ok(Err(error)) = receiver.try_recv() {
+                    warn!(
+                        "Operation {} of a checked batch failed: {}; skipping the rest",
+                        index, error
+                    );
+                    result = Err(ObjectRequestError::BatchOperation {
+                        index,
+                        error: Box::new(error),
+                    });
+                    break;
+                }
+            }
+            complete(&batch.completion, result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manage_objects::request::object::{MeshId, ObjectMaterial, ObjectShape, ObjectSize};
+    use uuid::Uuid;
+
+    fn object_id() -> ObjectId {
+        ObjectId {
+            uuid: Uuid::now_v7(),
+        }
+    }
+
+    fn properties(shape: ObjectShape) -> ObjectProperties {
+        ObjectProperties {
+            color: Color::WHITE,
+            material: ObjectMaterial::default(),
+            size: ObjectSize::Uniform(1.0),
+            shape,
+            dimensions: None,
+        }
+    }
+
+    fn spawn(object_id: &ObjectId, parent: Option<&ObjectId>) -> BatchOperation {
+        BatchOperation::Spawn(SpawnObjectRequest {
+            object_id: object_id.clone(),
+            object_properties: properties(ObjectShape::Cube),
+            position: Vec3::ZERO,
+            parent: parent.cloned(),
+            completion: None,
+        })
+    }
+
+    fn set_transform(object_id: &ObjectId) -> BatchOperation {
+        BatchOperation::SetTransform(SetObjectTransformRequest {
+            object_id: object_id.clone(),
+            position: Some(Vec3::X),
+            rotation: None,
+            scale: None,
+            timestamp: None,
+            velocity: None,
+            angular_velocity: None,
+            completion: None,
+        })
+    }
+
+    fn set_shape(object_id: &ObjectId, shape: ObjectShape) -> BatchOperation {
+        BatchOperation::SetProperties(SetObjectPropertiesRequest {
+            object_id: object_id.clone(),
+            color: None,
+            material: None,
+            shape: Some(shape),
+            size: None,
+            dimensions: None,
+            completion: None,
+        })
+    }
+
+    fn despawn(object_id: &ObjectId) -> BatchOperation {
+        BatchOperation::Despawn(DespawnObjectRequest {
+            object_id: object_id.clone(),
+            completion: None,
+        })
+    }
+
+    /// Checks the operations against an empty scene, returning the index and error of the first
+    /// one that fails.
+    fn check(operations: &[BatchOperation]) -> Result<(), (usize, ObjectRequestError)> {
+        let mut world = World::new();
+        world.init_resource::<ObjectIndex>();
+        world.init_resource::<RegisteredMeshes>();
+        let mut scene_params = SystemState::<BatchSceneParams>::new(&mut world);
+
+        match BatchScene::new(&scene_params.get(&world)).check(operations) {
+            Ok(()) => Ok(()),
+            Err(ObjectRequestError::BatchOperation { index, error }) => Err((index, *error)),
+            Err(error) => panic!("unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn operations_see_the_objects_spawned_before_them() {
+        let parent = object_id();
+        let child = object_id();
+
+        assert_eq!(
+            check(&[
+                spawn(&parent, None),
+                spawn(&child, Some(&parent)),
+                set_transform(&child),
+                set_shape(&child, ObjectShape::Sphere),
+                despawn(&child),
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn operations_before_the_spawn_fail() {
+        let object = object_id();
+
+        assert_eq!(
+            check(&[set_transform(&object), spawn(&object, None)]),
+            Err((0, ObjectRequestError::NotFound(object.clone())))
+        );
+        assert_eq!(
+            check(&[spawn(&object, None), spawn(&object, None)]),
+            Err((1, ObjectRequestError::AlreadyExists(object)))
+        );
+    }
+
+    #[test]
+    fn operations_after_the_despawn_fail() {
+        let object = object_id();
+
+        assert_eq!(
+            check(&[
+                spawn(&object, None),
+                despawn(&object),
+                set_transform(&object)
+            ]),
+            Err((2, ObjectRequestError::NotFound(object)))
+        );
+    }
+
+    #[test]
+    fn despawned_objects_can_be_spawned_again() {
+        let object = object_id();
+
+        assert_eq!(
+            check(&[
+                spawn(&object, None),
+                despawn(&object),
+                spawn(&object, None),
+                set_transform(&object),
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn despawning_a_parent_despawns_its_descendants() {
+        let parent = object_id();
+        let child = object_id();
+        let grandchild = object_id();
+
+        assert_eq!(
+            check(&[
+                spawn(&parent, None),
+                spawn(&child, Some(&parent)),
+                spawn(&grandchild, Some(&child)),
+                despawn(&parent),
+                despawn(&grandchild),
+            ]),
+            Err((4, ObjectRequestError::NotFound(grandchild)))
+        );
+    }
+
+    #[test]
+    fn respawned_objects_are_not_descendants_of_their_old_parent() {
+        let parent = object_id();
+        let child = object_id();
+
+        assert_eq!(
+            check(&[
+                spawn(&parent, None),
+                spawn(&child, Some(&parent)),
+                despawn(&child),
+                spawn(&child, None),
+                despawn(&parent),
+                set_transform(&child),
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn unregistered_meshes_fail() {
+        let object = object_id();
+        let mesh_id = MeshId {
+            uuid: Uuid::now_v7(),
+        };
+
+        assert_eq!(
+            check(&[
+                spawn(&object, None),
+                set_shape(&object, ObjectShape::Mesh(mesh_id.clone())),
+            ]),
+            Err((1, ObjectRequestError::MeshNotFound(mesh_id)))
+        );
+    }
+}
@@ -0,0 +1,988 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::object::{
+    Completion, ObjectAssets, ObjectChange, ObjectChanged, ObjectId, ObjectIndex, ObjectProperties,
+    ObjectRequest, ObjectRequestError, TargetPosition, TargetRotation, TargetScale, Velocity,
+    complete,
+};
+
+/// Rate, per second, at which the playback time of [`SimulationClock`] is steered towards the
+/// newest timestamp minus the delay.
+const CLOCK_CATCH_UP_RATE: f64 = 2.0;
+
+/// Drift, in seconds, past which [`SimulationClock`] jumps to the newest timestamp minus the delay
+/// instead of catching up, e.g. when the simulation restarts.
+const CLOCK_RESYNC_THRESHOLD: f64 = 1.0;
+
+pub struct MotionRequestPlugin;
+
+impl Plugin for MotionRequestPlugin {
+    /// Initializes smooth movement settings and registers the systems moving objects towards their
+    /// targets.
+    ///
+    /// Objects are moved after every object request of the frame has been handled, so they start
+    /// heading for a new target in the frame it arrives. Timestamped samples, keyframe animations
+    /// and velocities are applied just before, setting the targets of the objects they belong to.
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SmoothMovementSettings>()
+            .init_resource::<SimulationClock>()
+            .add_event::<SetInterpolationRequest>()
+            .add_event::<SetObjectAnimationRequest>()
+            .add_systems(
+                Update,
+                (
+                    SetInterpolationRequest::event_handler,
+                    SetObjectAnimationRequest::event_handler,
+                    advance_simulation_clock,
+                    play_timestamped_samples,
+                    play_keyframe_animations,
+                    integrate_velocities,
+                    smooth_movement_system,
+                )
+                    .chain()
+                    .after(super::process_requests)
+                    .after(ObjectRequest::event_handler),
+            );
+    }
+}
+
+/// Requests that change how objects move.
+#[derive(Debug)]
+pub enum MotionRequest {
+    SetInterpolation(SetInterpolationRequest),
+    SetObjectAnimation(SetObjectAnimationRequest),
+}
+
+/// Event writers for every kind of motion request.
+#[derive(SystemParam)]
+pub struct MotionRequestWriters<'w> {
+    set_interpolation: EventWriter<'w, SetInterpolationRequest>,
+    set_object_animation: EventWriter<'w, SetObjectAnimationRequest>,
+}
+
+impl MotionRequestWriters<'_> {
+    /// Emits the event corresponding to the given motion request.
+    pub fn write(&mut self, request: MotionRequest) {
+        match request {
+            MotionRequest::SetInterpolation(set_interpolation_request) => {
+                self.set_interpolation.write(set_interpolation_request);
+            }
+            MotionRequest::SetObjectAnimation(set_object_animation_request) => {
+                self.set_object_animation
+                    .write(set_object_animation_request);
+            }
+        }
+    }
+}
+
+/// Resource holding the interpolation mode of objects without one of their own.
+#[derive(Resource, Debug, Default)]
+pub struct SmoothMovementSettings {
+    pub mode: InterpolationMode,
+}
+
+/// How an object moves from where it is drawn towards its target transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpolationMode {
+    /// Jumps to the target as soon as it changes.
+    Snap,
+    /// Covers `speed` times the frame time of the remaining distance every frame, so it depends
+    /// on the frame rate.
+    Lerp { speed: f32 },
+    /// Closes the remaining distance exponentially at `decay_rate` per second, whatever the frame
+    /// rate.
+    ExponentialDecay { decay_rate: f32 },
+    /// Moves at a constant `speed` in units per second and turns at `angular_speed` in radians
+    /// per second; the scale changes at `speed` too.
+    Linear { speed: f32, angular_speed: f32 },
+    /// Goes from where the object was when the target changed to the target in `duration`,
+    /// following the easing curve.
+    Tween {
+        duration: Duration,
+        easing: EaseFunction,
+    },
+    /// Keeps moving at the velocity estimated from the last two target positions until the next
+    /// one arrives, for at most `max_duration`, while closing the distance like
+    /// [`InterpolationMode::ExponentialDecay`].
+    Extrapolate {
+        decay_rate: f32,
+        max_duration: Duration,
+    },
+}
+
+impl Default for InterpolationMode {
+    fn default() -> Self {
+        InterpolationMode::ExponentialDecay { decay_rate: 10.0 }
+    }
+}
+
+impl InterpolationMode {
+    /// Returns `true` if every speed and rate is finite and positive.
+    pub fn is_valid(&self) -> bool {
+        let is_positive = |value: f32| value.is_finite() && value > 0.0;
+        match *self {
+            InterpolationMode::Snap | InterpolationMode::Tween { .. } => true,
+            InterpolationMode::Lerp { speed } => is_positive(speed),
+            InterpolationMode::ExponentialDecay { decay_rate }
+            | InterpolationMode::Extrapolate { decay_rate, .. } => is_positive(decay_rate),
+            InterpolationMode::Linear {
+                speed,
+                angular_speed,
+            } => is_positive(speed) && is_positive(angular_speed),
+        }
+    }
+
+    /// Moves `transform` one frame of `delta` seconds towards `target`.
+    fn step(
+        &self,
+        transform: &mut Transform,
+        target: &Transform,
+        motion: &MotionState,
+        now: Duration,
+        delta: f32,
+    ) {
+        match *self {
+            InterpolationMode::Snap => {
+                *transform = *target;
+            }
+            InterpolationMode::Lerp { speed } => {
+                let alpha = (delta * speed).clamp(0.0, 1.0);
+                transform.translation = transform.translation.lerp(target.translation, alpha);
+                transform.rotation = transform.rotation.slerp(target.rotation, alpha);
+                transform.scale = transform.scale.lerp(target.scale, alpha);
+            }
+            InterpolationMode::ExponentialDecay { decay_rate } => {
+                nudge(transform, target, decay_rate, delta);
+            }
+            InterpolationMode::Linear {
+                speed,
+                angular_speed,
+            } => {
+                transform.translation = transform
+                    .translation
+                    .move_towards(target.translation, speed * delta);
+                transform.rotation = transform
+                    .rotation
+                    .rotate_towards(target.rotation, angular_speed * delta);
+                transform.scale = transform.scale.move_towards(target.scale, speed * delta);
+            }
+            InterpolationMode::Tween { duration, easing } => {
+                let elapsed = now.saturating_sub(motion.changed_at);
+                let progress = if duration.is_zero() {
+                    1.0
+                } else {
+                    elapsed.as_secs_f32() / duration.as_secs_f32()
+                };
+                let eased = easing.sample_clamped(progress.min(1.0));
+                let start = &motion.start;
+                transform.translation = start.translation.lerp(target.translation, eased);
+                transform.rotation = start.rotation.slerp(target.rotation, eased);
+                transform.scale = start.scale.lerp(target.scale, eased);
+            }
+            InterpolationMode::Extrapolate {
+                decay_rate,
+                max_duration,
+            } => {
+                let since_update = motion
+                    .last_target
+                    .map_or(Duration::ZERO, |(_, received_at)| {
+                        now.saturating_sub(received_at)
+                    });
+                // Past that, updates have most likely stopped and guessing would only drift.
+                let lead = if since_update <= max_duration {
+                    motion.velocity * since_update.as_secs_f32()
+                } else {
+                    Vec3::ZERO
+                };
+                let predicted = Transform {
+                    translation: target.translation + lead,
+                    ..*target
+                };
+                nudge(transform, &predicted, decay_rate, delta);
+            }
+        }
+    }
+}
+
+/// Moves `transform` towards `target` by exponential decay, independently of the frame rate.
+fn nudge(transform: &mut Transform, target: &Transform, decay_rate: f32, delta: f32) {
+    transform
+        .translation
+        .smooth_nudge(&target.translation, decay_rate, delta);
+    transform
+        .rotation
+        .smooth_nudge(&target.rotation, decay_rate, delta);
+    transform
+        .scale
+        .smooth_nudge(&target.scale, decay_rate, delta);
+}
+
+/// Resource mapping the simulation time carried by timestamped updates to the time the scene is
+/// drawn at.
+///
+/// The scene is drawn a fixed delay behind the newest timestamp, so that objects are usually
+/// between two samples and move smoothly however irregularly the updates arrive. Timestamps are
+/// expected to advance at about the speed of the wall clock.
+#[derive(Resource, Debug)]
+pub struct SimulationClock {
+    /// How far behind the newest timestamp the scene is drawn.
+    pub delay: Duration,
+    /// The simulation time the scene is drawn at, once a timestamped update has arrived.
+    playback_time: Option<f64>,
+    /// The newest timestamp received.
+    latest_timestamp: Option<f64>,
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        SimulationClock::new(Duration::from_millis(100))
+    }
+}
+
+impl SimulationClock {
+    /// Creates a clock drawing the scene `delay` behind the newest timestamp.
+    pub fn new(delay: Duration) -> Self {
+        SimulationClock {
+            delay,
+            playback_time: None,
+            latest_timestamp: None,
+        }
+    }
+
+    /// Returns the simulation time the scene is drawn at, if a timestamped update has arrived.
+    pub fn playback_time(&self) -> Option<f64> {
+        self.playback_time
+    }
+
+    /// Records the timestamp of an update.
+    pub(super) fn observe(&mut self, timestamp: f64) {
+        self.latest_timestamp = Some(
+            self.latest_timestamp
+                .map_or(timestamp, |latest| latest.max(timestamp)),
+        );
+    }
+}
+
+/// A transform an object should have at a given simulation time.
+#[derive(Debug, Clone, Copy)]
+struct TransformSample {
+    timestamp: f64,
+    transform: Transform,
+}
+
+/// Component overriding the interpolation mode of [`SmoothMovementSettings`] for one object.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Interpolation(pub InterpolationMode);
+
+/// Component tracking how the target of an object has been changing.
+#[derive(Component, Debug, Default)]
+pub struct MotionState {
+    /// The transform of the object when its target last changed, where tweens start from.
+    start: Transform,
+    /// When the target last changed.
+    changed_at: Duration,
+    /// The last target position and when it was received.
+    last_target: Option<(Vec3, Duration)>,
+    /// Velocity estimated from the last two target positions.
+    velocity: Vec3,
+    /// Timestamped transforms that are still to be played back, oldest first.
+    samples: VecDeque<TransformSample>,
+}
+
+impl MotionState {
+    /// Buffers the transform the object should have at `timestamp` and returns it.
+    ///
+    /// Fields that are `None` are taken from the sample just before, or from `target` if there is
+    /// none. A sample with the same timestamp is replaced.
+    pub(super) fn push_sample(
+        &mut self,
+        timestamp: f64,
+        position: Option<Vec3>,
+        rotation: Option<Quat>,
+        scale: Option<Vec3>,
+        target: Transform,
+    ) -> Transform {
+        let index = self
+            .samples
+            .partition_point(|sample| sample.timestamp < timestamp);
+        let base = index
+            .checked_sub(1)
+            .map_or(target, |previous| self.samples[previous].transform);
+        let transform = Transform {
+            translation: position.unwrap_or(base.translation),
+            rotation: rotation.unwrap_or(base.rotation),
+            scale: scale.unwrap_or(base.scale),
+        };
+        let sample = TransformSample {
+            timestamp,
+            transform,
+        };
+
+        match self.samples.get_mut(index) {
+            Some(existing) if existing.timestamp == timestamp => *existing = sample,
+            _ => self.samples.insert(index, sample),
+        }
+        transform
+    }
+
+    /// Drops the buffered samples, so that the object follows untimestamped updates again.
+    pub(super) fn clear_samples(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns `true` if the targets of the object are set by timestamped samples.
+    fn is_playing_samples(&self) -> bool {
+        !self.samples.is_empty()
+    }
+
+    /// Forgets the samples that are entirely in the past and returns the transform at
+    /// `playback_time`, interpolated between the samples around it.
+    ///
+    /// Holds the first or last sample when `playback_time` is outside of the buffer.
+    fn sample_at(&mut self, playback_time: f64) -> Option<Transform> {
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|next| next.timestamp <= playback_time)
+        {
+            self.samples.pop_front();
+        }
+
+        let previous = self.samples.front()?;
+        let Some(next) = self.samples.get(1) else {
+            return Some(previous.transform);
+        };
+        let progress = ((playback_time - previous.timestamp)
+            / (next.timestamp - previous.timestamp))
+            .clamp(0.0, 1.0) as f32;
+        Some(Transform {
+            translation: previous
+                .transform
+                .translation
+                .lerp(next.transform.translation, progress),
+            rotation: previous
+                .transform
+                .rotation
+                .slerp(next.transform.rotation, progress),
+            scale: previous
+                .transform
+                .scale
+                .lerp(next.transform.scale, progress),
+        })
+    }
+}
+
+/// How a keyframe animation carries on once it reaches its last keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationRepeat {
+    /// Stops at the last keyframe.
+    #[default]
+    Once,
+    /// Starts over from the first keyframe.
+    Loop,
+    /// Plays backwards to the first keyframe, then forwards again.
+    PingPong,
+}
+
+/// The transform and color an object should have at a point of an animation.
+///
+/// Fields that are `None` are interpolated from the keyframes around that set them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    /// Seconds since the start of the animation.
+    pub time: f32,
+    pub position: Option<Vec3>,
+    pub rotation: Option<Quat>,
+    pub scale: Option<Vec3>,
+    pub color: Option<Color>,
+}
+
+/// Keyframes played back by the viewer itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectAnimation {
+    /// Sorted by time.
+    pub keyframes: Vec<Keyframe>,
+    pub repeat: AnimationRepeat,
+    /// How many seconds of the animation are played per second.
+    pub speed: f32,
+}
+
+impl ObjectAnimation {
+    /// Returns `true` if there is a keyframe, the keyframe times are finite, non-negative and
+    /// sorted, and the speed is finite and positive.
+    pub fn is_valid(&self) -> bool {
+        !self.keyframes.is_empty()
+            && self
+                .keyframes
+                .iter()
+                .all(|keyframe| keyframe.time.is_finite() && keyframe.time >= 0.0)
+            && self
+                .keyframes
+                .windows(2)
+                .all(|pair| pair[0].time <= pair[1].time)
+            && self.speed.is_finite()
+            && self.speed > 0.0
+    }
+}
+
+/// The keyframes of an animation that set one property, as `(time, value)` pairs sorted by time.
+#[derive(Debug)]
+struct Track<T>(Vec<(f32, T)>);
+
+impl<T: Copy> Track<T> {
+    fn new(keyframes: &[Keyframe], value: impl Fn(&Keyframe) -> Option<T>) -> Self {
+        Track(
+            keyframes
+                .iter()
+                .filter_map(|keyframe| value(keyframe).map(|value| (keyframe.time, value)))
+                .collect(),
+        )
+    }
+
+    /// Returns the value at `time`, interpolated between the keyframes around it, or `None` if no
+    /// keyframe sets the property.
+    ///
+    /// Holds the first or last value when `time` is outside of the keyframes.
+    fn sample(&self, time: f32, interpolate: impl Fn(T, T, f32) -> T) -> Option<T> {
+        let index = self
+            .0
+            .partition_point(|&(keyframe_time, _)| keyframe_time <= time);
+        let Some(&(next_time, next)) = self.0.get(index) else {
+            return self.0.last().map(|&(_, value)| value);
+        };
+        let Some(&(previous_time, previous)) = index.checked_sub(1).map(|i| &self.0[i]) else {
+            return Some(next);
+        };
+        let progress = (time - previous_time) / (next_time - previous_time);
+        Some(interpolate(previous, next, progress))
+    }
+}
+
+/// Component playing keyframes on an object, setting its targets and color every frame.
+#[derive(Component, Debug)]
+pub struct KeyframeAnimation {
+    positions: Track<Vec3>,
+    rotations: Track<Quat>,
+    scales: Track<Vec3>,
+    colors: Track<Color>,
+    /// The time of the last keyframe.
+    duration: f32,
+    repeat: AnimationRepeat,
+    speed: f32,
+    /// Seconds of the animation played so far, scaled by the speed; wrapped to one cycle when
+    /// repeating, so that it keeps its precision however long the animation plays.
+    elapsed: f32,
+    /// Whether the animation reached its last keyframe and no longer sets anything.
+    finished: bool,
+    /// The last color applied, until the object is given it for good when the animation ends.
+    color: Option<Color>,
+    /// The material drawing the animated color, used by this object alone so that shared
+    /// materials are left untouched.
+    material: Option<Handle<StandardMaterial>>,
+}
+
+impl KeyframeAnimation {
+    pub fn new(animation: &ObjectAnimation) -> Self {
+        let keyframes = &animation.keyframes;
+        KeyframeAnimation {
+            positions: Track::new(keyframes, |keyframe| keyframe.position),
+            rotations: Track::new(keyframes, |keyframe| keyframe.rotation),
+            scales: Track::new(keyframes, |keyframe| keyframe.scale),
+            colors: Track::new(keyframes, |keyframe| keyframe.color),
+            duration: keyframes.last().map_or(0.0, |keyframe| keyframe.time),
+            repeat: animation.repeat,
+            speed: animation.speed,
+            elapsed: 0.0,
+            finished: false,
+            color: None,
+            material: None,
+        }
+    }
+
+    /// Advances the animation by `delta` seconds of real time.
+    fn advance(&mut self, delta: f32) {
+        self.elapsed += delta * self.speed;
+        if self.duration > 0.0 {
+            match self.repeat {
+                AnimationRepeat::Once => {}
+                AnimationRepeat::Loop => self.elapsed = self.elapsed.rem_euclid(self.duration),
+                AnimationRepeat::PingPong => {
+                    self.elapsed = self.elapsed.rem_euclid(2.0 * self.duration);
+                }
+            }
+        }
+    }
+
+    /// Returns the time within the keyframes the animation is at, and whether it has ended.
+    fn playhead(&self) -> (f32, bool) {
+        let duration = self.duration;
+        if duration <= 0.0 {
+            return (0.0, self.repeat == AnimationRepeat::Once);
+        }
+        match self.repeat {
+            AnimationRepeat::Once => (self.elapsed.min(duration), self.elapsed >= duration),
+            AnimationRepeat::Loop => (self.elapsed % duration, false),
+            AnimationRepeat::PingPong => {
+                let phase = self.elapsed % (2.0 * duration);
+                (duration - (phase - duration).abs(), false)
+            }
+        }
+    }
+}
+
+/// Gives an object that was animated back the shared material of its properties, keeping the color
+/// the animation left it with.
+fn commit_animated_color(
+    object_id: &ObjectId,
+    animation: &mut KeyframeAnimation,
+    props: &mut ObjectProperties,
+    material: &mut MeshMaterial3d<StandardMaterial>,
+    object_assets: &mut ObjectAssets,
+    changed_writer: &mut EventWriter<ObjectChanged>,
+) {
+    let Some(color) = animation.color.take() else {
+        return;
+    };
+    props.color = color;
+    material.0 = object_assets.material(props);
+    object_assets.mark_stale();
+    changed_writer.write(ObjectChanged {
+        object_id: object_id.clone(),
+        change: ObjectChange::PropertiesChanged {
+            object_properties: props.clone(),
+        },
+    });
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct SetInterpolationRequest {
+    /// The object to set the mode of; `None` sets the mode of every object without its own.
+    pub object_id: Option<ObjectId>,
+    /// The new mode; `None` goes back to the default mode, or to the global one for an object.
+    pub mode: Option<InterpolationMode>,
+    pub completion: Option<Completion>,
+}
+
+impl SetInterpolationRequest {
+    /// Handles interpolation events by setting the global mode or the mode of single objects.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        mut settings: ResMut<SmoothMovementSettings>,
+        object_index: Res<ObjectIndex>,
+        mut commands: Commands,
+    ) {
+        for event in event_reader.read() {
+            let Some(object_id) = &event.object_id else {
+                trace!("Setting global interpolation mode to {:?}", event.mode);
+                settings.mode = event.mode.unwrap_or_default();
+                complete(&event.completion, Ok(()));
+                continue;
+            };
+
+            let Some(entity) = object_index.get(object_id) else {
+                trace!("Object {} not found; ignoring interpolation", object_id);
+                complete(
+                    &event.completion,
+                    Err(ObjectRequestError::NotFound(object_id.clone())),
+                );
+                continue;
+            };
+
+            trace!(
+                "Setting interpolation mode of object {} to {:?}",
+                object_id, event.mode
+            );
+            match event.mode {
+                Some(mode) => {
+                    commands.entity(entity).insert(Interpolation(mode));
+                }
+                None => {
+                    commands.entity(entity).remove::<Interpolation>();
+                }
+            }
+            complete(&event.completion, Ok(()));
+        }
+    }
+}
+
+/// Query data needed to play a keyframe animation on an object.
+type AnimatedObjectData = (
+    &'static ObjectId,
+    Option<&'static mut KeyframeAnimation>,
+    &'static mut TargetPosition,
+    &'static mut TargetRotation,
+    &'static mut TargetScale,
+    &'static mut ObjectProperties,
+    &'static mut MeshMaterial3d<StandardMaterial>,
+);
+
+#[derive(Debug, Clone, Event)]
+pub struct SetObjectAnimationRequest {
+    pub object_id: ObjectId,
+    /// The animation to play from its start; `None` stops the current one where it is.
+    pub animation: Option<ObjectAnimation>,
+    pub completion: Option<Completion>,
+}
+
+impl SetObjectAnimationRequest {
+    /// Handles animation events by starting, replacing or stopping the animation of objects.
+    ///
+    /// An animation that is replaced or stopped leaves the object with the color it was at.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        mut changed_writer: EventWriter<ObjectChanged>,
+        object_index: Res<ObjectIndex>,
+        mut object_assets: ObjectAssets,
+        mut commands: Commands,
+        mut query: Query<AnimatedObjectData>,
+    ) {
+        for event in event_reader.read() {
+            let Some((entity, Ok(data))) = object_index
+                .get(&event.object_id)
+                .map(|entity| (entity, query.get_mut(entity)))
+            else {
+                trace!("Object {} not found; ignoring animation", event.object_id);
+                complete(
+                    &event.completion,
+                    Err(ObjectRequestError::NotFound(event.object_id.clone())),
+                );
+                continue;
+            };
+            let (
+                object_id,
+                animation,
+                target_pos,
+                target_rot,
+                target_scale,
+                mut props,
+                mut material,
+            ) = data;
+
+            trace!(
+                "Setting animation of object {} to {:?}",
+                event.object_id, event.animation
+            );
+
+            if let Some(mut animation) = animation {
+                commit_animated_color(
+                    object_id,
+                    &mut animation,
+                    &mut props,
+                    &mut material,
+                    &mut object_assets,
+                    &mut changed_writer,
+                );
+            }
+            match &event.animation {
+                Some(animation) => {
+                    commands
+                        .entity(entity)
+                        .insert(KeyframeAnimation::new(animation));
+                }
+                None => {
+                    commands.entity(entity).remove::<KeyframeAnimation>();
+                    changed_writer.write(ObjectChanged {
+                        object_id: object_id.clone(),
+                        change: ObjectChange::Moved {
+                            target_transform: Transform {
+                                translation: target_pos.0,
+                                rotation: target_rot.0,
+                                scale: target_scale.0,
+                            },
+                        },
+                    });
+                }
+            }
+            complete(&event.completion, Ok(()));
+        }
+    }
+}
+
+/// Advances the playback time by the frame time, steering it towards the newest timestamp minus the
+/// delay.
+fn advance_simulation_clock(time: Res<Time>, mut clock: ResMut<SimulationClock>) {
+    let Some(latest_timestamp) = clock.latest_timestamp else {
+        return;
+    };
+    let target = latest_timestamp - clock.delay.as_secs_f64();
+    let delta = time.delta_secs_f64();
+
+    clock.playback_time = Some(match clock.playback_time {
+        Some(playback_time) => {
+            let playback_time = playback_time + delta;
+            let drift = target - playback_time;
+            if drift.abs() > CLOCK_RESYNC_THRESHOLD {
+                target
+            } else {
+                playback_time + drift * (delta * CLOCK_CATCH_UP_RATE).min(1.0)
+            }
+        }
+        None => target,
+    });
+}
+
+/// Sets the targets of objects with buffered samples to their transform at the playback time.
+fn play_timestamped_samples(
+    clock: Res<SimulationClock>,
+    mut query: Query<(
+        &mut MotionState,
+        &mut TargetPosition,
+        &mut TargetRotation,
+        &mut TargetScale,
+    )>,
+) {
+    let Some(playback_time) = clock.playback_time() else {
+        return;
+    };
+
+    for (mut motion, mut target_pos, mut target_rot, mut target_scale) in &mut query {
+        if !motion.is_playing_samples() {
+            continue;
+        }
+        let Some(transform) = motion.sample_at(playback_time) else {
+            continue;
+        };
+        target_pos.0 = transform.translation;
+        target_rot.0 = transform.rotation;
+        target_scale.0 = transform.scale;
+    }
+}
+
+/// Advances keyframe animations by the frame time and sets the targets and color of their objects.
+///
+/// Animations that play once are removed the frame after they reach their last keyframe, once the
+/// object has been moved there.
+fn play_keyframe_animations(
+    time: Res<Time>,
+    mut changed_writer: EventWriter<ObjectChanged>,
+    mut object_assets: ObjectAssets,
+    mut commands: Commands,
+    mut query: Query<(Entity, AnimatedObjectData), With<KeyframeAnimation>>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, data) in &mut query {
+        let (
+            object_id,
+            animation,
+            mut target_pos,
+            mut target_rot,
+            mut target_scale,
+            mut props,
+            mut material,
+        ) = data;
+        let Some(mut animation) = animation else {
+            continue;
+        };
+        if animation.finished {
+            commands.entity(entity).remove::<KeyframeAnimation>();
+            continue;
+        }
+
+        animation.advance(delta);
+        let (playhead, finished) = animation.playhead();
+        if let Some(position) = animation.positions.sample(playhead, Vec3::lerp) {
+            target_pos.0 = position;
+        }
+        if let Some(rotation) = animation.rotations.sample(playhead, Quat::slerp) {
+            target_rot.0 = rotation;
+        }
+        if let Some(scale) = animation.scales.sample(playhead, Vec3::lerp) {
+            target_scale.0 = scale;
+        }
+        if let Some(color) = animation
+            .colors
+            .sample(playhead, |previous, next, progress| {
+                previous.mix(&next, progress)
+            })
+        {
+            let animation = &mut *animation;
+            animation.color = Some(color);
+            let handle = object_assets.unshared_material(&mut animation.material, &props, color);
+            if material.0 != handle {
+                material.0 = handle;
+                object_assets.mark_stale();
+            }
+        }
+
+        if finished {
+            trace!("Animation of object {} finished", object_id);
+            animation.finished = true;
+            commit_animated_color(
+                object_id,
+                &mut animation,
+                &mut props,
+                &mut material,
+                &mut object_assets,
+                &mut changed_writer,
+            );
+            changed_writer.write(ObjectChanged {
+                object_id: object_id.clone(),
+                change: ObjectChange::Moved {
+                    target_transform: Transform {
+                        translation: target_pos.0,
+                        rotation: target_rot.0,
+                        scale: target_scale.0,
+                    },
+                },
+            });
+        }
+    }
+}
+
+/// Moves the targets of objects at their velocity, so that they keep moving between updates.
+///
+/// Objects playing timestamped samples or keyframe animations are left to them.
+fn integrate_velocities(
+    time: Res<Time>,
+    mut query: Query<
+        (
+            &Velocity,
+            &MotionState,
+            &mut TargetPosition,
+            &mut TargetRotation,
+        ),
+        Without<KeyframeAnimation>,
+    >,
+) {
+    let delta = time.delta_secs();
+
+    for (velocity, motion, mut target_pos, mut target_rot) in &mut query {
+        if motion.is_playing_samples() {
+            continue;
+        }
+        // Targets are only touched when moving, so that interpolation does not restart every frame.
+        if velocity.linear != Vec3::ZERO {
+            target_pos.0 += velocity.linear * delta;
+        }
+        if velocity.angular != Vec3::ZERO {
+            target_rot.0 =
+                (Quat::from_scaled_axis(velocity.angular * delta) * target_rot.0).normalize();
+        }
+    }
+}
+
+/// Query data needed to move an object towards its target.
+type MovementData = (
+    &'static mut Transform,
+    Ref<'static, TargetPosition>,
+    Ref<'static, TargetRotation>,
+    Ref<'static, TargetScale>,
+    &'static mut MotionState,
+    Option<&'static Interpolation>,
+    Has<KeyframeAnimation>,
+);
+
+/// Moves each object towards its target position, rotation and scale using its interpolation
+/// mode, or the global one if it has none.
+///
+/// Objects playing timestamped samples or keyframe animations follow their targets exactly, as
+/// those are already interpolated.
+fn smooth_movement_system(
+    time: Res<Time>,
+    settings: Res<SmoothMovementSettings>,
+    mut query: Query<MovementData>,
+) {
+    let now = time.elapsed();
+    let delta = time.delta_secs();
+
+    query.par_iter_mut().for_each(
+        |(
+            mut transform,
+            target_pos,
+            target_rot,
+            target_scale,
+            mut motion,
+            interpolation,
+            animated,
+        )| {
+            if target_pos.is_changed() {
+                if let Some((last_position, received_at)) = motion.last_target {
+                    let elapsed = now.saturating_sub(received_at).as_secs_f32();
+                    if elapsed > 0.0 {
+                        motion.velocity = (target_pos.0 - last_position) / elapsed;
+                    }
+                }
+                motion.last_target = Some((target_pos.0, now));
+            }
+            if target_pos.is_changed() || target_rot.is_changed() || target_scale.is_changed() {
+                motion.start = *transform;
+                motion.changed_at = now;
+            }
+
+            let target = Transform {
+                translation: target_pos.0,
+                rotation: target_rot.0,
+                scale: target_scale.0,
+            };
+            let mode = if animated || motion.is_playing_samples() {
+                &InterpolationMode::Snap
+            } else {
+                interpolation.map_or(&settings.mode, |interpolation| &interpolation.0)
+            };
+            mode.step(&mut transform, &target, &motion, now, delta);
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(repeat: AnimationRepeat) -> KeyframeAnimation {
+        let keyframe = |time, x| Keyframe {
+            time,
+            position: Some(Vec3::X * x),
+            rotation: None,
+            scale: None,
+            color: None,
+        };
+        KeyframeAnimation::new(&ObjectAnimation {
+            keyframes: vec![keyframe(0.0, 0.0), keyframe(2.0, 1.0)],
+            repeat,
+            speed: 1.0,
+        })
+    }
+
+    #[test]
+    fn repeating_animations_keep_playing_after_days() {
+        let delta = 1.0 / 60.0;
+        for repeat in [AnimationRepeat::Loop, AnimationRepeat::PingPong] {
+            let mut animation = animation(repeat);
+            // Ten days of frames would take too long, so the animation starts close to that.
+            animation.elapsed = 864_000.0;
+            animation.advance(delta);
+
+            let (before, _) = animation.playhead();
+            animation.advance(delta);
+            let (after, finished) = animation.playhead();
+            assert!(!finished);
+            assert!((after - before).abs() > delta / 2.0, "{repeat:?}");
+        }
+    }
+
+    #[test]
+    fn wrapping_keeps_the_playhead() {
+        let mut looping = animation(AnimationRepeat::Loop);
+        looping.advance(5.0);
+        assert_eq!(looping.playhead(), (1.0, false));
+
+        let mut ping_pong = animation(AnimationRepeat::PingPong);
+        ping_pong.advance(5.0);
+        assert_eq!(ping_pong.playhead(), (1.0, false));
+        ping_pong.advance(1.5);
+        assert_eq!(ping_pong.playhead(), (1.5, false));
+    }
+
+    #[test]
+    fn once_stops_at_the_last_keyframe() {
+        let mut once = animation(AnimationRepeat::Once);
+        once.advance(1.0);
+        assert_eq!(once.playhead(), (1.0, false));
+        once.advance(5.0);
+        assert_eq!(once.playhead(), (2.0, true));
+    }
+}
@@ -1,162 +1,1592 @@
+use bevy::diagnostic::FrameCount;
+use bevy::ecs::system::{SystemParam, SystemState};
 use bevy::prelude::*;
+use bevy::render::mesh::MeshAabb;
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::sync::Arc;
+use thiserror::Error;
 use uuid::Uuid;
 
-// Resource specifying smooth interpolation speed and enable/disable flag
-#[derive(Resource)]
-pub struct SmoothMovementSettings {
-    /// Interpolation speed (higher value moves faster towards the target)
-    pub speed: f32,
-    /// Whether interpolation is enabled
-    pub enabled: bool,
+use super::annotation::AnnotationId;
+use super::motion::{MotionState, SimulationClock};
+use super::primitive::PrimitiveId;
+use crate::manage_objects::global::{OBJECT_CHANGE_BROADCAST, ObjectChangeNotification};
+use crate::types::ResponseSender;
+
+/// Resource mapping the ID of every spawned object to its entity.
+///
+/// Kept in sync by the spawn and despawn handlers; every handler that targets an object by ID
+/// looks it up here instead of scanning all objects.
+#[derive(Resource, Debug, Default)]
+pub struct ObjectIndex {
+    entities: HashMap<ObjectId, Entity>,
 }
 
-impl Default for SmoothMovementSettings {
-    fn default() -> Self {
-        Self {
-            speed: 10.0,
-            enabled: true,
+impl ObjectIndex {
+    /// Returns the entity of the object with the given ID, if it exists.
+    pub fn get(&self, object_id: &ObjectId) -> Option<Entity> {
+        self.entities.get(object_id).copied()
+    }
+
+    /// Returns `true` if an object with the given ID exists.
+    pub fn contains(&self, object_id: &ObjectId) -> bool {
+        self.entities.contains_key(object_id)
+    }
+
+    /// Returns the number of indexed objects.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if no objects are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn insert(&mut self, object_id: ObjectId, entity: Entity) {
+        self.entities.insert(object_id, entity);
+    }
+
+    fn remove(&mut self, object_id: &ObjectId) -> Option<Entity> {
+        self.entities.remove(object_id)
+    }
+}
+
+/// Resource sharing one mesh per shape and size and one material per color and surface between all objects.
+///
+/// Objects that share both handles are drawn as a single instanced batch, so large populations
+/// don't cost one mesh and one material each.
+#[derive(Resource, Debug, Default)]
+pub struct ObjectAssetCache {
+    meshes: HashMap<MeshKey, Handle<Mesh>>,
+    materials: HashMap<MaterialKey, Handle<StandardMaterial>>,
+    /// Set when an object stopped using a handle, so unused assets are released at the end of the frame.
+    stale: bool,
+}
+
+impl ObjectAssetCache {
+    /// Returns the shared mesh for the shape and dimensions of the given properties, creating it on first use.
+    ///
+    /// Fails if the properties refer to a mesh that is not registered.
+    pub fn mesh(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        registered_meshes: &RegisteredMeshes,
+        object_properties: &ObjectProperties,
+    ) -> Result<Handle<Mesh>, ObjectRequestError> {
+        let key = object_properties.mesh_key(registered_meshes)?;
+        if let Some(handle) = self.meshes.get(&key) {
+            return Ok(handle.clone());
+        }
+
+        let handle = meshes.add(object_properties.mesh(registered_meshes)?);
+        self.meshes.insert(key, handle.clone());
+        Ok(handle)
+    }
+
+    /// Returns the shared material for the color and surface of the given properties, creating it on first use.
+    pub fn material(
+        &mut self,
+        materials: &mut Assets<StandardMaterial>,
+        object_properties: &ObjectProperties,
+    ) -> Handle<StandardMaterial> {
+        let ObjectProperties {
+            color, material, ..
+        } = object_properties;
+        self.materials
+            .entry(material.key(*color))
+            .or_insert_with(|| materials.add(material.standard_material(*color)))
+            .clone()
+    }
+
+    /// Returns the number of distinct meshes currently shared between objects.
+    pub fn mesh_count(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// Returns the number of distinct materials currently shared between objects.
+    pub fn material_count(&self) -> usize {
+        self.materials.len()
+    }
+
+    fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    /// Drops the handles no object uses anymore, which frees their assets.
+    fn release_unused(&mut self) {
+        self.meshes.retain(|_, handle| is_handle_shared(handle));
+        self.materials.retain(|_, handle| is_handle_shared(handle));
+        self.stale = false;
+    }
+}
+
+/// The shared meshes and materials of objects, together with everything needed to build new ones.
+#[derive(SystemParam)]
+pub struct ObjectAssets<'w> {
+    cache: ResMut<'w, ObjectAssetCache>,
+    registered_meshes: Res<'w, RegisteredMeshes>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+impl ObjectAssets<'_> {
+    /// Returns the shared mesh for the given properties, failing if they refer to a mesh that is not registered.
+    pub fn mesh(
+        &mut self,
+        object_properties: &ObjectProperties,
+    ) -> Result<Handle<Mesh>, ObjectRequestError> {
+        self.cache
+            .mesh(&mut self.meshes, &self.registered_meshes, object_properties)
+    }
+
+    /// Returns the shared material for the color and surface of the given properties.
+    pub fn material(&mut self, object_properties: &ObjectProperties) -> Handle<StandardMaterial> {
+        self.cache.material(&mut self.materials, object_properties)
+    }
+
+    /// Returns a material used by one object alone, with the surface of the given properties in `color`.
+    ///
+    /// The material in `unshared` is updated in place if there is one, so that the color of an object
+    /// can change every frame without a shared material being created for each color.
+    pub fn unshared_material(
+        &mut self,
+        unshared: &mut Option<Handle<StandardMaterial>>,
+        object_properties: &ObjectProperties,
+        color: Color,
+    ) -> Handle<StandardMaterial> {
+        let material = object_properties.material.standard_material(color);
+        if let Some(handle) = unshared.as_ref()
+            && let Some(existing) = self.materials.get_mut(handle)
+        {
+            *existing = material;
+            return handle.clone();
+        }
+
+        let handle = self.materials.add(material);
+        *unshared = Some(handle.clone());
+        handle
+    }
+
+    /// Notes that an object stopped using a handle, so unused assets are released at the end of the frame.
+    pub fn mark_stale(&mut self) {
+        self.cache.mark_stale();
+    }
+}
+
+/// Returns `true` if a strong handle is held anywhere besides the cache itself.
+fn is_handle_shared<A: Asset>(handle: &Handle<A>) -> bool {
+    match handle {
+        Handle::Strong(handle) => Arc::strong_count(handle) > 1,
+        Handle::Weak(_) => false,
+    }
+}
+
+/// Resource holding the meshes registered by clients, which objects refer to by ID.
+#[derive(Resource, Debug, Default)]
+pub struct RegisteredMeshes {
+    meshes: HashMap<MeshId, RegisteredMesh>,
+}
+
+impl RegisteredMeshes {
+    /// Returns the registered mesh with the given ID, failing if there is none.
+    pub fn get(&self, mesh_id: &MeshId) -> Result<&RegisteredMesh, ObjectRequestError> {
+        self.meshes
+            .get(mesh_id)
+            .ok_or_else(|| ObjectRequestError::MeshNotFound(mesh_id.clone()))
+    }
+
+    /// Returns `true` if a mesh with the given ID is registered.
+    pub fn contains(&self, mesh_id: &MeshId) -> bool {
+        self.meshes.contains_key(mesh_id)
+    }
+
+    /// Returns the number of registered meshes.
+    pub fn len(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// Returns `true` if no meshes are registered.
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+    }
+
+    fn insert(&mut self, mesh_id: MeshId, mesh: RegisteredMesh) {
+        self.meshes.insert(mesh_id, mesh);
+    }
+}
+
+/// A mesh registered by a client, together with its bounding box.
+#[derive(Debug, Clone)]
+pub struct RegisteredMesh {
+    mesh: Mesh,
+    center: Vec3,
+    extents: Vec3,
+}
+
+impl RegisteredMesh {
+    /// Wraps a triangle mesh, returning `None` if it has no vertex positions.
+    pub fn new(mesh: Mesh) -> Option<Self> {
+        let aabb = mesh.compute_aabb()?;
+        Some(RegisteredMesh {
+            mesh,
+            center: aabb.center.into(),
+            extents: Vec3::from(aabb.half_extents) * 2.0,
+        })
+    }
+
+    /// Returns the full extents of the bounding box of the mesh as it was registered.
+    pub fn extents(&self) -> Vec3 {
+        self.extents
+    }
+
+    /// Returns the bounding box of the mesh at the given uniform size, keeping its proportions.
+    pub fn uniform_extents(&self, size: f32) -> Vec3 {
+        let longest = self.extents.max_element();
+        if longest > 0.0 {
+            self.extents * (size / longest)
+        } else {
+            Vec3::ZERO
         }
     }
+
+    /// Returns the mesh centered on the origin and stretched to fill a bounding box with the given full extents.
+    ///
+    /// Axes along which the mesh is flat are left unscaled.
+    pub fn mesh(&self, extents: Vec3) -> Mesh {
+        let scale = Vec3::select(
+            self.extents.cmpgt(Vec3::ZERO),
+            extents / self.extents,
+            Vec3::ONE,
+        );
+        self.mesh
+            .clone()
+            .translated_by(-self.center)
+            .scaled_by(scale)
+    }
 }
 
 /// Component holding the current target position for each object
 #[derive(Component)]
 pub struct TargetPosition(pub Vec3);
 
+/// Component holding the current target rotation for each object
+#[derive(Component)]
+pub struct TargetRotation(pub Quat);
+
+/// Component holding the current target scale for each object
+#[derive(Component)]
+pub struct TargetScale(pub Vec3);
+
+/// Component holding the velocity the targets of each object are moved at every frame until the
+/// next update
+#[derive(Component, Debug, Default)]
+pub struct Velocity {
+    /// In units per second.
+    pub linear: Vec3,
+    /// Rotation axis scaled by the angular speed in radians per second.
+    pub angular: Vec3,
+}
+
 pub struct ObjectRequestPlugin;
 
 impl Plugin for ObjectRequestPlugin {
-    /// Initializes smooth movement settings and registers object request systems.
+    /// Initializes object resources and registers object request systems.
     fn build(&self, app: &mut App) {
-        app
-            // Initialize resource using Default
-            .init_resource::<SmoothMovementSettings>()
-            .add_event::<SpawnObjectRequest>()
-            .add_systems(Update, SpawnObjectRequest::event_handler)
-            .add_event::<SetObjectPositionRequest>()
-            .add_systems(Update, SetObjectPositionRequest::event_handler)
-            .add_systems(Update, smooth_movement_system);
+        app.init_resource::<ObjectIndex>()
+            .init_resource::<ObjectAssetCache>()
+            .init_resource::<RegisteredMeshes>()
+            .add_event::<ObjectRequest>()
+            .add_systems(
+                Update,
+                ObjectRequest::event_handler.after(super::process_requests),
+            )
+            .add_event::<ObjectChanged>()
+            .add_systems(
+                PostUpdate,
+                (broadcast_object_changes, release_unused_object_assets),
+            );
+    }
+}
+
+/// Reasons an object request could not be applied.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ObjectRequestError {
+    #[error("{0} not found")]
+    NotFound(ObjectId),
+    #[error("{0} already exists")]
+    AlreadyExists(ObjectId),
+    #[error("The dimensions do not match the shape of {0}")]
+    DimensionsMismatch(ObjectId),
+    #[error("{0} cannot be attached to itself or one of its descendants")]
+    ParentCycle(ObjectId),
+    #[error("{0} not found")]
+    MeshNotFound(MeshId),
+    #[error("{0} not found")]
+    AnnotationNotFound(AnnotationId),
+    #[error("{0} not found")]
+    PrimitiveNotFound(PrimitiveId),
+    #[error("{0} already exists")]
+    MeshAlreadyExists(MeshId),
+    #[error("Operation {index} of the batch: {error}")]
+    BatchOperation {
+        index: usize,
+        error: Box<ObjectRequestError>,
+    },
+}
+
+/// Receives the outcome of an object request once the handler has processed it.
+pub type Completion = ResponseSender<Result<(), ObjectRequestError>>;
+
+/// Reports the outcome of a request to whoever is waiting for it, if anyone.
+pub(super) fn complete(completion: &Option<Completion>, result: Result<(), ObjectRequestError>) {
+    if let Some(completion) = completion {
+        completion.send(result);
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Event)]
 pub enum ObjectRequest {
     SetPosition(SetObjectPositionRequest),
     Spawn(SpawnObjectRequest),
+    Despawn(DespawnObjectRequest),
+    SetTransform(SetObjectTransformRequest),
+    SetProperties(SetObjectPropertiesRequest),
+    RegisterMesh(RegisterMeshRequest),
+    SetParent(SetObjectParentRequest),
 }
 
-#[derive(Debug, Clone, Event)]
+/// Everything the object request handlers access, one entry per kind of request.
+pub type ObjectRequestParams<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        SpawnObjectParams<'static, 'static>,
+        SetObjectPositionParams<'static, 'static>,
+        SetObjectTransformParams<'static, 'static>,
+        SetObjectPropertiesParams<'static, 'static>,
+        DespawnObjectParams<'static, 'static>,
+        RegisterMeshParams<'static>,
+        SetObjectParentParams<'static, 'static>,
+    ),
+>;
+
+impl ObjectRequest {
+    /// Applies the object requests of this frame one by one, in the order they arrived.
+    ///
+    /// Commands are flushed after every request that needs it, so that the requests queued
+    /// behind it already see its effect.
+    pub fn event_handler(
+        world: &mut World,
+        event_reader: &mut SystemState<EventReader<Self>>,
+        params: &mut SystemState<ObjectRequestParams>,
+    ) {
+        let requests: Vec<Self> = event_reader.get_mut(world).read().cloned().collect();
+
+        // Spawns don't depend on each other's commands, so consecutive ones share a single sync.
+        for run in requests.chunk_by(|request, next| {
+            !request.needs_sync() || matches!((request, next), (Self::Spawn(_), Self::Spawn(_)))
+        }) {
+            let mut object_request_params = params.get_mut(world);
+            for request in run {
+                request.apply(&mut object_request_params);
+            }
+            params.apply(world);
+        }
+    }
+
+    /// Whether later requests only see the effect of this one once its commands are applied.
+    fn needs_sync(&self) -> bool {
+        // A spawned entity only exists, and a new parent only shows in the hierarchy, once
+        // their commands are applied.
+        matches!(self, Self::Spawn(_) | Self::SetParent(_))
+    }
+
+    /// Applies a single request and reports its outcome.
+    pub(super) fn apply(&self, params: &mut ObjectRequestParams) {
+        match self {
+            Self::Spawn(request) => request.apply(&mut params.p0()),
+            Self::SetPosition(request) => request.apply(&mut params.p1()),
+            Self::SetTransform(request) => request.apply(&mut params.p2()),
+            Self::SetProperties(request) => request.apply(&mut params.p3()),
+            Self::Despawn(request) => request.apply(&mut params.p4()),
+            Self::RegisterMesh(request) => request.apply(&mut params.p5()),
+            Self::SetParent(request) => request.apply(&mut params.p6()),
+        }
+    }
+}
+
+/// Event writer for object requests.
+#[derive(SystemParam)]
+pub struct ObjectRequestWriters<'w> {
+    object_request: EventWriter<'w, ObjectRequest>,
+}
+
+impl ObjectRequestWriters<'_> {
+    /// Emits the given object request.
+    pub fn write(&mut self, request: ObjectRequest) {
+        self.object_request.write(request);
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SetObjectPositionRequest {
     pub object_id: ObjectId,
     pub position: Vec3,
+    /// Simulation time of the position, in seconds; `None` moves the object right away.
+    pub timestamp: Option<f64>,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`SetObjectPositionRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct SetObjectPositionParams<'w, 's> {
+    changed_writer: EventWriter<'w, ObjectChanged>,
+    object_index: Res<'w, ObjectIndex>,
+    clock: ResMut<'w, SimulationClock>,
+    query: Query<
+        'w,
+        's,
+        (
+            &'static mut TargetPosition,
+            &'static TargetRotation,
+            &'static TargetScale,
+            &'static mut MotionState,
+        ),
+    >,
 }
 
 impl SetObjectPositionRequest {
-    /// Handles incoming position events by updating entities’ target positions.
-    pub fn event_handler(
-        mut event_reader: EventReader<Self>,
-        mut query: Query<(&ObjectId, &mut TargetPosition)>,
-    ) {
-        for event in event_reader.read() {
-            for (object_id, mut target_pos) in query.iter_mut() {
-                if *object_id == event.object_id {
-                    trace!(
-                        "Updating target position of object {} to {:?}",
-                        object_id, event.position
-                    );
-                    target_pos.0 = event.position;
+    /// Updates the target position of the object, or buffers it for playback if it is timestamped.
+    pub(super) fn apply(&self, params: &mut SetObjectPositionParams) {
+        let SetObjectPositionParams {
+            changed_writer,
+            object_index,
+            clock,
+            query,
+        } = params;
+        let Some(Ok((mut target_pos, target_rot, target_scale, mut motion))) = object_index
+            .get(&self.object_id)
+            .map(|entity| query.get_mut(entity))
+        else {
+            trace!("Object {} not found; ignoring position", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        trace!(
+            "Updating target position of object {} to {:?} at {:?}",
+            self.object_id, self.position, self.timestamp
+        );
+        let target_transform = Transform {
+            translation: target_pos.0,
+            rotation: target_rot.0,
+            scale: target_scale.0,
+        };
+        let target_transform = match self.timestamp {
+            Some(timestamp) => {
+                clock.observe(timestamp);
+                motion.push_sample(timestamp, Some(self.position), None, None, target_transform)
+            }
+            None => {
+                motion.clear_samples();
+                target_pos.0 = self.position;
+                Transform {
+                    translation: self.position,
+                    ..target_transform
                 }
             }
+        };
+        changed_writer.write(ObjectChanged {
+            object_id: self.object_id.clone(),
+            change: ObjectChange::Moved { target_transform },
+        });
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetObjectTransformRequest {
+    pub object_id: ObjectId,
+    /// New target position; `None` keeps the current one.
+    pub position: Option<Vec3>,
+    /// New target rotation; `None` keeps the current one.
+    pub rotation: Option<Quat>,
+    /// New target scale; `None` keeps the current one.
+    pub scale: Option<Vec3>,
+    /// Simulation time of the transform, in seconds; `None` moves the object right away.
+    pub timestamp: Option<f64>,
+    /// New velocity; `None` keeps the current one.
+    pub velocity: Option<Vec3>,
+    /// New angular velocity; `None` keeps the current one.
+    pub angular_velocity: Option<Vec3>,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`SetObjectTransformRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct SetObjectTransformParams<'w, 's> {
+    changed_writer: EventWriter<'w, ObjectChanged>,
+    object_index: Res<'w, ObjectIndex>,
+    clock: ResMut<'w, SimulationClock>,
+    query: Query<
+        'w,
+        's,
+        (
+            &'static mut TargetPosition,
+            &'static mut TargetRotation,
+            &'static mut TargetScale,
+            &'static mut MotionState,
+            &'static mut Velocity,
+        ),
+    >,
+}
+
+impl SetObjectTransformRequest {
+    /// Updates the target position, rotation, scale and velocity of the object, or buffers the
+    /// transform for playback if it is timestamped.
+    pub(super) fn apply(&self, params: &mut SetObjectTransformParams) {
+        let SetObjectTransformParams {
+            changed_writer,
+            object_index,
+            clock,
+            query,
+        } = params;
+        let Some(Ok((mut target_pos, mut target_rot, mut target_scale, mut motion, mut velocity))) =
+            object_index
+                .get(&self.object_id)
+                .map(|entity| query.get_mut(entity))
+        else {
+            trace!("Object {} not found; ignoring transform", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        trace!(
+            "Updating target transform of object {} to position {:?}, rotation {:?}, scale {:?} at {:?} with velocity {:?} and angular velocity {:?}",
+            self.object_id,
+            self.position,
+            self.rotation,
+            self.scale,
+            self.timestamp,
+            self.velocity,
+            self.angular_velocity
+        );
+        if let Some(linear) = self.velocity {
+            velocity.linear = linear;
+        }
+        if let Some(angular) = self.angular_velocity {
+            velocity.angular = angular;
         }
+        let target_transform = Transform {
+            translation: target_pos.0,
+            rotation: target_rot.0,
+            scale: target_scale.0,
+        };
+        let target_transform = match self.timestamp {
+            Some(timestamp) => {
+                clock.observe(timestamp);
+                motion.push_sample(
+                    timestamp,
+                    self.position,
+                    self.rotation,
+                    self.scale,
+                    target_transform,
+                )
+            }
+            None => {
+                motion.clear_samples();
+                if let Some(position) = self.position {
+                    target_pos.0 = position;
+                }
+                if let Some(rotation) = self.rotation {
+                    target_rot.0 = rotation;
+                }
+                if let Some(scale) = self.scale {
+                    target_scale.0 = scale;
+                }
+                Transform {
+                    translation: target_pos.0,
+                    rotation: target_rot.0,
+                    scale: target_scale.0,
+                }
+            }
+        };
+        changed_writer.write(ObjectChanged {
+            object_id: self.object_id.clone(),
+            change: ObjectChange::Moved { target_transform },
+        });
+        complete(&self.completion, Ok(()));
     }
 }
 
-#[derive(Debug, Clone, Event)]
+#[derive(Debug, Clone)]
 pub struct SpawnObjectRequest {
     pub object_id: ObjectId,
     pub object_properties: ObjectProperties,
+    /// Relative to the parent, if there is one.
     pub position: Vec3,
+    /// The object to attach the new one to.
+    pub parent: Option<ObjectId>,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`SpawnObjectRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct SpawnObjectParams<'w, 's> {
+    changed_writer: EventWriter<'w, ObjectChanged>,
+    object_index: ResMut<'w, ObjectIndex>,
+    object_assets: ObjectAssets<'w>,
+    commands: Commands<'w, 's>,
 }
 
 impl SpawnObjectRequest {
-    /// Handles spawn events by creating new entities with given properties.
-    pub fn event_handler(
-        mut event_reader: EventReader<Self>,
-        mut commands: Commands,
-        mut meshes: ResMut<Assets<Mesh>>,
-        mut materials: ResMut<Assets<StandardMaterial>>,
-    ) {
-        for event in event_reader.read() {
-            let props = &event.object_properties;
-            let pos = event.position;
-            match props.shape {
-                ObjectShape::Cube => {
-                    trace!("Spawning cube with size: {}", props.size);
-                    commands.spawn((
-                        event.object_id.clone(),
-                        Name::new(event.object_id.to_string()),
-                        Mesh3d(meshes.add(Cuboid::from_size(Vec3::splat(props.size)))),
-                        MeshMaterial3d(materials.add(StandardMaterial {
-                            base_color: props.color,
-                            ..default()
-                        })),
-                        Transform::from_translation(pos),
-                        TargetPosition(pos),
-                    ));
+    /// Creates a new entity with the given properties, rejecting IDs that are already taken.
+    pub(super) fn apply(&self, params: &mut SpawnObjectParams) {
+        if params.object_index.contains(&self.object_id) {
+            trace!("Object {} already exists; ignoring spawn", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::AlreadyExists(self.object_id.clone())),
+            );
+            return;
+        }
+
+        let parent = match &self.parent {
+            Some(parent_id) => match params.object_index.get(parent_id) {
+                Some(parent) => Some(parent),
+                None => {
+                    trace!(
+                        "Parent {} of object {} not found; ignoring spawn",
+                        parent_id, self.object_id
+                    );
+                    complete(
+                        &self.completion,
+                        Err(ObjectRequestError::NotFound(parent_id.clone())),
+                    );
+                    return;
                 }
-                ObjectShape::Sphere => {
-                    trace!("Spawning sphere with size: {}", props.size);
-                    commands.spawn((
-                        event.object_id.clone(),
-                        Name::new(event.object_id.to_string()),
-                        Mesh3d(meshes.add(Sphere::new(props.size))),
-                        MeshMaterial3d(materials.add(StandardMaterial {
-                            base_color: props.color,
-                            ..default()
-                        })),
-                        Transform::from_translation(pos),
-                        TargetPosition(pos),
-                    ));
+            },
+            None => None,
+        };
+
+        let props = &self.object_properties;
+        let pos = self.position;
+        let mesh = match params.object_assets.mesh(props) {
+            Ok(mesh) => mesh,
+            Err(e) => {
+                trace!("Cannot spawn object {}: {}", self.object_id, e);
+                complete(&self.completion, Err(e));
+                return;
+            }
+        };
+
+        trace!("Spawning {:?} with size: {:?}", props.shape, props.size);
+        let mut entity = params.commands.spawn((
+            self.object_id.clone(),
+            Name::new(self.object_id.to_string()),
+            Mesh3d(mesh),
+            MeshMaterial3d(params.object_assets.material(props)),
+            props.clone(),
+            Transform::from_translation(pos),
+            TargetPosition(pos),
+            TargetRotation(Quat::IDENTITY),
+            TargetScale(Vec3::ONE),
+            MotionState::default(),
+            Velocity::default(),
+        ));
+        if let Some(parent) = parent {
+            entity.insert(ChildOf(parent));
+        }
+        params
+            .object_index
+            .insert(self.object_id.clone(), entity.id());
+        params.changed_writer.write(ObjectChanged {
+            object_id: self.object_id.clone(),
+            change: ObjectChange::Spawned {
+                object_properties: props.clone(),
+                transform: Transform::from_translation(pos),
+                parent: self.parent.clone(),
+            },
+        });
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetObjectPropertiesRequest {
+    pub object_id: ObjectId,
+    /// New color; `None` keeps the current one.
+    pub color: Option<Color>,
+    /// New surface; `None` keeps the current one.
+    pub material: Option<ObjectMaterial>,
+    /// New shape; `None` keeps the current one.
+    pub shape: Option<ObjectShape>,
+    /// New size; `None` keeps the current one. Setting it discards the current dimensions.
+    pub size: Option<ObjectSize>,
+    /// New dimensions, which must match the resulting shape; `None` keeps the current ones if they still fit.
+    pub dimensions: Option<ObjectDimensions>,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`SetObjectPropertiesRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct SetObjectPropertiesParams<'w, 's> {
+    changed_writer: EventWriter<'w, ObjectChanged>,
+    object_index: Res<'w, ObjectIndex>,
+    object_assets: ObjectAssets<'w>,
+    query: Query<
+        'w,
+        's,
+        (
+            &'static mut ObjectProperties,
+            &'static mut Mesh3d,
+            &'static mut MeshMaterial3d<StandardMaterial>,
+        ),
+    >,
+}
+
+impl SetObjectPropertiesRequest {
+    /// Returns the properties an object with `props` ends up with, failing if the new dimensions do
+    /// not match the resulting shape.
+    pub fn updated_properties(
+        &self,
+        props: &ObjectProperties,
+    ) -> Result<ObjectProperties, ObjectRequestError> {
+        let mut new_props = props.clone();
+        if let Some(shape) = &self.shape {
+            new_props.shape = shape.clone();
+        }
+        if self
+            .dimensions
+            .as_ref()
+            .is_some_and(|dimensions| dimensions.shape() != new_props.shape)
+        {
+            return Err(ObjectRequestError::DimensionsMismatch(
+                self.object_id.clone(),
+            ));
+        }
+
+        if let Some(size) = self.size {
+            new_props.size = size;
+            new_props.dimensions = None;
+        }
+        if self.dimensions.is_some() {
+            new_props.dimensions = self.dimensions.clone();
+        } else if new_props
+            .dimensions
+            .as_ref()
+            .is_some_and(|dimensions| dimensions.shape() != new_props.shape)
+        {
+            new_props.dimensions = None;
+        }
+        if let Some(color) = self.color {
+            new_props.color = color;
+        }
+        if let Some(material) = self.material {
+            new_props.material = material;
+        }
+        Ok(new_props)
+    }
+
+    /// Returns `true` if the request changes the mesh of the object.
+    pub fn changes_mesh(&self) -> bool {
+        self.shape.is_some() || self.size.is_some() || self.dimensions.is_some()
+    }
+
+    /// Switches the object to the shared mesh and material matching its new properties.
+    pub(super) fn apply(&self, params: &mut SetObjectPropertiesParams) {
+        let Some(Ok((mut props, mut mesh, mut material))) = params
+            .object_index
+            .get(&self.object_id)
+            .map(|entity| params.query.get_mut(entity))
+        else {
+            trace!("Object {} not found; ignoring properties", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        let new_props = match self.updated_properties(&props) {
+            Ok(new_props) => new_props,
+            Err(e) => {
+                trace!(
+                    "Dimensions {:?} do not match the shape of object {}; ignoring properties",
+                    self.dimensions, self.object_id
+                );
+                complete(&self.completion, Err(e));
+                return;
+            }
+        };
+
+        trace!(
+            "Updating properties of object {} to color {:?}, material {:?}, shape {:?}, size {:?}, dimensions {:?}",
+            self.object_id, self.color, self.material, self.shape, self.size, self.dimensions
+        );
+
+        // Materials and meshes are shared with other objects, so they are swapped rather
+        // than modified.
+        if self.changes_mesh() {
+            match params.object_assets.mesh(&new_props) {
+                Ok(new_mesh) => mesh.0 = new_mesh,
+                Err(e) => {
+                    trace!("Cannot update object {}: {}", self.object_id, e);
+                    complete(&self.completion, Err(e));
+                    return;
                 }
             }
+            params.object_assets.mark_stale();
+        }
+
+        if self.color.is_some() || self.material.is_some() {
+            material.0 = params.object_assets.material(&new_props);
+            params.object_assets.mark_stale();
         }
+        *props = new_props;
+
+        params.changed_writer.write(ObjectChanged {
+            object_id: self.object_id.clone(),
+            change: ObjectChange::PropertiesChanged {
+                object_properties: props.clone(),
+            },
+        });
+        complete(&self.completion, Ok(()));
     }
 }
 
-/// Smoothly interpolates each entity’s transform toward its target position.
-fn smooth_movement_system(
-    time: Res<Time>,
-    settings: Res<SmoothMovementSettings>,
-    mut query: Query<(&mut Transform, &TargetPosition)>,
-) {
-    // Calculate interpolation ratio
-    let alpha = (time.delta_secs() * settings.speed).clamp(0.0, 1.0);
-    query.par_iter_mut().for_each(|(mut transform, target)| {
-        if settings.enabled {
-            // With interpolation
-            transform.translation = transform.translation.lerp(target.0, alpha);
-        } else {
-            // Without interpolation
-            transform.translation = target.0;
+#[derive(Debug, Clone)]
+pub struct SetObjectParentRequest {
+    pub object_id: ObjectId,
+    /// The object to attach to; `None` detaches the object from its current parent.
+    pub parent: Option<ObjectId>,
+    /// Keeps the object where it is in the world instead of keeping its transform relative to
+    /// the parent, which is used as is otherwise.
+    pub keep_world_transform: bool,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`SetObjectParentRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct SetObjectParentParams<'w, 's> {
+    changed_writer: EventWriter<'w, ObjectChanged>,
+    object_index: Res<'w, ObjectIndex>,
+    commands: Commands<'w, 's>,
+    query: Query<
+        'w,
+        's,
+        (
+            &'static mut Transform,
+            &'static mut TargetPosition,
+            &'static mut TargetRotation,
+            &'static mut TargetScale,
+        ),
+    >,
+    hierarchy: Query<'w, 's, (Option<&'static ChildOf>, &'static GlobalTransform)>,
+}
+
+impl SetObjectParentRequest {
+    /// Attaches or detaches the object, rejecting changes that would create a cycle.
+    pub(super) fn apply(&self, params: &mut SetObjectParentParams) {
+        let Some(entity) = params.object_index.get(&self.object_id) else {
+            trace!("Object {} not found; ignoring parent", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        let parent = match &self.parent {
+            Some(parent_id) => {
+                let Some(parent) = params.object_index.get(parent_id) else {
+                    trace!(
+                        "Parent {} of object {} not found; ignoring parent",
+                        parent_id, self.object_id
+                    );
+                    complete(
+                        &self.completion,
+                        Err(ObjectRequestError::NotFound(parent_id.clone())),
+                    );
+                    return;
+                };
+                Some(parent)
+            }
+            None => None,
+        };
+
+        // The parents changed by earlier requests have already been applied.
+        let hierarchy = &params.hierarchy;
+        let parent_of = |entity: Entity| {
+            hierarchy
+                .get(entity)
+                .ok()
+                .and_then(|(child_of, _)| child_of.map(ChildOf::parent))
+        };
+
+        let mut ancestor = parent;
+        while let Some(current) = ancestor {
+            if current == entity {
+                break;
+            }
+            ancestor = parent_of(current);
+        }
+        if ancestor.is_some() {
+            trace!(
+                "Attaching object {} to {:?} would create a cycle; ignoring parent",
+                self.object_id, self.parent
+            );
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::ParentCycle(self.object_id.clone())),
+            );
+            return;
+        }
+
+        let Ok((mut transform, mut target_pos, mut target_rot, mut target_scale)) =
+            params.query.get_mut(entity)
+        else {
+            trace!("Object {} not found; ignoring parent", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        if self.keep_world_transform {
+            // Based on the transforms of the parents as of the last propagation.
+            let global_transform = |parent: Option<Entity>| {
+                parent
+                    .and_then(|parent| hierarchy.get(parent).ok())
+                    .map_or(GlobalTransform::IDENTITY, |(_, global)| *global)
+            };
+            let old_parent = global_transform(parent_of(entity));
+            let new_parent = global_transform(parent);
+            let reparent =
+                GlobalTransform::from(new_parent.affine().inverse() * old_parent.affine());
+
+            *transform = reparent.mul_transform(*transform).compute_transform();
+            let target = reparent
+                .mul_transform(Transform {
+                    translation: target_pos.0,
+                    rotation: target_rot.0,
+                    scale: target_scale.0,
+                })
+                .compute_transform();
+            target_pos.0 = target.translation;
+            target_rot.0 = target.rotation;
+            target_scale.0 = target.scale;
+        }
+
+        trace!("Attaching object {} to {:?}", self.object_id, self.parent);
+        match parent {
+            Some(parent) => params.commands.entity(entity).insert(ChildOf(parent)),
+            None => params.commands.entity(entity).remove::<ChildOf>(),
+        };
+
+        params.changed_writer.write(ObjectChanged {
+            object_id: self.object_id.clone(),
+            change: ObjectChange::ParentChanged {
+                parent: self.parent.clone(),
+                target_transform: Transform {
+                    translation: target_pos.0,
+                    rotation: target_rot.0,
+                    scale: target_scale.0,
+                },
+            },
+        });
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RegisterMeshRequest {
+    pub mesh_id: MeshId,
+    pub mesh: RegisteredMesh,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`RegisterMeshRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct RegisterMeshParams<'w> {
+    registered_meshes: ResMut<'w, RegisteredMeshes>,
+}
+
+impl RegisterMeshRequest {
+    /// Stores the mesh for objects to refer to, rejecting IDs that are already taken.
+    pub(super) fn apply(&self, params: &mut RegisterMeshParams) {
+        if params.registered_meshes.contains(&self.mesh_id) {
+            trace!(
+                "Mesh {} already exists; ignoring registration",
+                self.mesh_id
+            );
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::MeshAlreadyExists(self.mesh_id.clone())),
+            );
+            return;
         }
-    });
+
+        trace!(
+            "Registering mesh {} with extents {:?}",
+            self.mesh_id,
+            self.mesh.extents()
+        );
+        params
+            .registered_meshes
+            .insert(self.mesh_id.clone(), self.mesh.clone());
+        complete(&self.completion, Ok(()));
+    }
 }
 
-#[derive(Debug, Component, Clone)]
+#[derive(Debug, Clone)]
+pub struct DespawnObjectRequest {
+    pub object_id: ObjectId,
+    pub completion: Option<Completion>,
+}
+
+/// Everything [`DespawnObjectRequest::apply`] touches.
+#[derive(SystemParam)]
+pub struct DespawnObjectParams<'w, 's> {
+    changed_writer: EventWriter<'w, ObjectChanged>,
+    object_index: ResMut<'w, ObjectIndex>,
+    asset_cache: ResMut<'w, ObjectAssetCache>,
+    commands: Commands<'w, 's>,
+    children: Query<'w, 's, &'static Children>,
+    object_ids: Query<'w, 's, &'static ObjectId>,
+}
+
+impl DespawnObjectRequest {
+    /// Removes the object together with its descendants; their shared assets are released once unused.
+    pub(super) fn apply(&self, params: &mut DespawnObjectParams) {
+        let Some(entity) = params.object_index.remove(&self.object_id) else {
+            trace!("Object {} not found; ignoring despawn", self.object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(self.object_id.clone())),
+            );
+            return;
+        };
+
+        trace!("Despawning object {}", self.object_id);
+        // Despawning an entity despawns its descendants as well.
+        params.commands.entity(entity).despawn();
+        params.asset_cache.mark_stale();
+        params.changed_writer.write(ObjectChanged {
+            object_id: self.object_id.clone(),
+            change: ObjectChange::Despawned,
+        });
+
+        for object_id in params
+            .object_ids
+            .iter_many(params.children.iter_descendants(entity))
+        {
+            if params.object_index.remove(object_id).is_some() {
+                trace!("Despawning descendant object {}", object_id);
+                params.changed_writer.write(ObjectChanged {
+                    object_id: object_id.clone(),
+                    change: ObjectChange::Despawned,
+                });
+            }
+        }
+        complete(&self.completion, Ok(()));
+    }
+}
+
+/// Emitted by the request handlers after a change has been applied to an object.
+#[derive(Debug, Clone, Event)]
+pub struct ObjectChanged {
+    pub object_id: ObjectId,
+    pub change: ObjectChange,
+}
+
+#[derive(Debug, Clone)]
+pub enum ObjectChange {
+    Spawned {
+        object_properties: ObjectProperties,
+        transform: Transform,
+        parent: Option<ObjectId>,
+    },
+    Despawned,
+    /// The target transform changed; the rendered transform follows through interpolation.
+    Moved {
+        target_transform: Transform,
+    },
+    PropertiesChanged {
+        object_properties: ObjectProperties,
+    },
+    /// The object was attached to another one, or detached if `parent` is `None`.
+    ParentChanged {
+        parent: Option<ObjectId>,
+        /// The target transform relative to the new parent.
+        target_transform: Transform,
+    },
+}
+
+/// Forwards the object changes applied during this frame to the gRPC watchers.
+fn broadcast_object_changes(
+    mut event_reader: EventReader<ObjectChanged>,
+    frame_count: Res<FrameCount>,
+) {
+    if OBJECT_CHANGE_BROADCAST.receiver_count() == 0 {
+        // Nobody is watching; don't bother cloning the events.
+        event_reader.clear();
+        return;
+    }
+
+    for event in event_reader.read() {
+        // Fails only when every watcher has disconnected in the meantime.
+        let _ = OBJECT_CHANGE_BROADCAST.send(ObjectChangeNotification {
+            frame: frame_count.0,
+            object_changed: event.clone(),
+        });
+    }
+}
+
+/// Releases the cached meshes and materials that no object uses anymore.
+///
+/// Runs after the despawn commands have been applied, so the despawned entities no longer hold
+/// their handles.
+fn release_unused_object_assets(mut asset_cache: ResMut<ObjectAssetCache>) {
+    if asset_cache.stale {
+        asset_cache.release_unused();
+    }
+}
+
+#[derive(Debug, Component, Clone, PartialEq, Eq, Hash)]
 pub enum ObjectShape {
     Cube,
     Sphere,
+    Cylinder,
+    Capsule,
+    Cone,
+    Torus,
+    Plane,
+    Tetrahedron,
+    /// A mesh registered by a client.
+    Mesh(MeshId),
+}
+
+impl ObjectShape {
+    /// Returns the bounding box of this shape at the given uniform size, keeping its natural proportions.
+    ///
+    /// The longest side of the box is `size`, so every shape fits in a cube with edges of that length.
+    /// Fails if the shape refers to a mesh that is not registered.
+    pub fn uniform_extents(
+        &self,
+        size: f32,
+        registered_meshes: &RegisteredMeshes,
+    ) -> Result<Vec3, ObjectRequestError> {
+        let extents = match self {
+            ObjectShape::Cube
+            | ObjectShape::Sphere
+            | ObjectShape::Cylinder
+            | ObjectShape::Cone
+            | ObjectShape::Tetrahedron => Vec3::splat(size),
+            ObjectShape::Capsule => Vec3::new(size / 2.0, size, size / 2.0),
+            ObjectShape::Torus => Vec3::new(size, size / 4.0, size),
+            ObjectShape::Plane => Vec3::new(size, 0.0, size),
+            ObjectShape::Mesh(mesh_id) => registered_meshes.get(mesh_id)?.uniform_extents(size),
+        };
+        Ok(extents)
+    }
+
+    /// Builds the mesh for this shape, stretched to fill a bounding box with the given full extents.
+    ///
+    /// Capsules keep round caps and tori keep a round tube when the X and Z extents are equal.
+    /// Planes ignore the Y extent. Fails if the shape refers to a mesh that is not registered.
+    pub fn mesh(
+        &self,
+        extents: Vec3,
+        registered_meshes: &RegisteredMeshes,
+    ) -> Result<Mesh, ObjectRequestError> {
+        let mesh = match self {
+            ObjectShape::Cube => Cuboid::from_size(extents).into(),
+            ObjectShape::Sphere => Mesh::from(Sphere::new(0.5)).scaled_by(extents),
+            ObjectShape::Cylinder => Mesh::from(Cylinder::new(0.5, 1.0)).scaled_by(extents),
+            ObjectShape::Capsule => {
+                let radius = extents.min_element() / 2.0;
+                let length = (extents.y - 2.0 * radius).max(0.0);
+                Mesh::from(Capsule3d::new(radius, length))
+                    .scaled_by(horizontal_stretch(extents, radius))
+            }
+            ObjectShape::Cone => Mesh::from(Cone::new(0.5, 1.0)).scaled_by(extents),
+            ObjectShape::Torus => {
+                let minor_radius = extents.y / 2.0;
+                let major_radius = (extents.x.min(extents.z) / 2.0 - minor_radius).max(0.0);
+                Mesh::from(Torus {
+                    minor_radius,
+                    major_radius,
+                })
+                .scaled_by(horizontal_stretch(extents, major_radius + minor_radius))
+            }
+            ObjectShape::Plane => {
+                Plane3d::new(Vec3::Y, Vec2::new(extents.x, extents.z) / 2.0).into()
+            }
+            ObjectShape::Tetrahedron => Mesh::from(Tetrahedron::default()).scaled_by(extents),
+            ObjectShape::Mesh(mesh_id) => registered_meshes.get(mesh_id)?.mesh(extents),
+        };
+        Ok(mesh)
+    }
+}
+
+/// Returns the scale stretching a shape that is round with the given radius in the XZ plane to the given extents.
+fn horizontal_stretch(extents: Vec3, radius: f32) -> Vec3 {
+    if radius > 0.0 {
+        Vec3::new(extents.x / (2.0 * radius), 1.0, extents.z / (2.0 * radius))
+    } else {
+        Vec3::ONE
+    }
+}
+
+/// Size of an object, describing the full extents of its bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectSize {
+    /// The longest side of the bounding box; the shape keeps its natural proportions.
+    Uniform(f32),
+    /// The full length along each axis; the shape is stretched to fill the box.
+    Extents(Vec3),
+}
+
+impl ObjectSize {
+    /// Returns the full extents of the bounding box of the given shape at this size.
+    pub fn extents(
+        &self,
+        shape: &ObjectShape,
+        registered_meshes: &RegisteredMeshes,
+    ) -> Result<Vec3, ObjectRequestError> {
+        match *self {
+            ObjectSize::Uniform(size) => shape.uniform_extents(size, registered_meshes),
+            ObjectSize::Extents(extents) => Ok(extents),
+        }
+    }
+
+    /// Returns `true` if the size is finite and positive along every axis.
+    pub fn is_valid(&self) -> bool {
+        let is_extent = |value: f32| value.is_finite() && value > 0.0;
+        match *self {
+            ObjectSize::Uniform(size) => is_extent(size),
+            ObjectSize::Extents(extents) => extents.to_array().into_iter().all(is_extent),
+        }
+    }
+}
+
+/// Shape-specific dimensions, used instead of the size when set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectDimensions {
+    Cuboid {
+        size: Vec3,
+    },
+    Sphere {
+        radius: f32,
+    },
+    Cylinder {
+        radius: f32,
+        height: f32,
+    },
+    /// `length` is the length of the cylindrical part, excluding the hemispheres.
+    Capsule {
+        radius: f32,
+        length: f32,
+    },
+    Cone {
+        radius: f32,
+        height: f32,
+    },
+    Torus {
+        minor_radius: f32,
+        major_radius: f32,
+    },
+    /// `size` is the full length along the X and Z axes.
+    Plane {
+        size: Vec2,
+    },
+}
+
+impl ObjectDimensions {
+    /// Returns the shape these dimensions apply to.
+    pub fn shape(&self) -> ObjectShape {
+        match self {
+            ObjectDimensions::Cuboid { .. } => ObjectShape::Cube,
+            ObjectDimensions::Sphere { .. } => ObjectShape::Sphere,
+            ObjectDimensions::Cylinder { .. } => ObjectShape::Cylinder,
+            ObjectDimensions::Capsule { .. } => ObjectShape::Capsule,
+            ObjectDimensions::Cone { .. } => ObjectShape::Cone,
+            ObjectDimensions::Torus { .. } => ObjectShape::Torus,
+            ObjectDimensions::Plane { .. } => ObjectShape::Plane,
+        }
+    }
+
+    /// Builds the mesh for these dimensions.
+    pub fn mesh(&self) -> Mesh {
+        match *self {
+            ObjectDimensions::Cuboid { size } => Cuboid::from_size(size).into(),
+            ObjectDimensions::Sphere { radius } => Sphere::new(radius).into(),
+            ObjectDimensions::Cylinder { radius, height } => Cylinder::new(radius, height).into(),
+            ObjectDimensions::Capsule { radius, length } => Capsule3d::new(radius, length).into(),
+            ObjectDimensions::Cone { radius, height } => Cone::new(radius, height).into(),
+            ObjectDimensions::Torus {
+                minor_radius,
+                major_radius,
+            } => Torus {
+                minor_radius,
+                major_radius,
+            }
+            .into(),
+            ObjectDimensions::Plane { size } => Plane3d::new(Vec3::Y, size / 2.0).into(),
+        }
+    }
+
+    /// Returns `true` if every length is finite and positive.
+    pub fn is_valid(&self) -> bool {
+        let is_length = |value: f32| value.is_finite() && value > 0.0;
+        match *self {
+            ObjectDimensions::Cuboid { size } => size.to_array().into_iter().all(is_length),
+            ObjectDimensions::Sphere { radius } => is_length(radius),
+            ObjectDimensions::Cylinder { radius, height }
+            | ObjectDimensions::Cone { radius, height } => is_length(radius) && is_length(height),
+            ObjectDimensions::Capsule { radius, length } => is_length(radius) && is_length(length),
+            ObjectDimensions::Torus {
+                minor_radius,
+                major_radius,
+            } => is_length(minor_radius) && is_length(major_radius),
+            ObjectDimensions::Plane { size } => size.to_array().into_iter().all(is_length),
+        }
+    }
+
+    /// Returns the lengths of these dimensions, padded with zeros.
+    fn values(&self) -> [f32; 3] {
+        match *self {
+            ObjectDimensions::Cuboid { size } => size.to_array(),
+            ObjectDimensions::Sphere { radius } => [radius, 0.0, 0.0],
+            ObjectDimensions::Cylinder { radius, height }
+            | ObjectDimensions::Cone { radius, height } => [radius, height, 0.0],
+            ObjectDimensions::Capsule { radius, length } => [radius, length, 0.0],
+            ObjectDimensions::Torus {
+                minor_radius,
+                major_radius,
+            } => [minor_radius, major_radius, 0.0],
+            ObjectDimensions::Plane { size } => [size.x, size.y, 0.0],
+        }
+    }
+}
+
+/// How the alpha of an object's color is used when drawing it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectAlphaMode {
+    /// The alpha is ignored.
+    Opaque,
+    /// Fully transparent where the alpha is below the cutoff, fully opaque elsewhere.
+    Mask(f32),
+    /// Blended with what is behind the object according to the alpha.
+    Blend,
+    /// Like `Blend`, with a color that is already multiplied by its alpha.
+    Premultiplied,
+    /// Adds the color to what is behind the object, which suits glows and light effects.
+    Add,
+    /// Multiplies what is behind the object by the color.
+    Multiply,
+}
+
+impl ObjectAlphaMode {
+    /// Returns `true` if the cutoff of a mask lies within `0..=1`.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ObjectAlphaMode::Mask(cutoff) => (0.0..=1.0).contains(cutoff),
+            _ => true,
+        }
+    }
+
+    fn alpha_mode(self) -> AlphaMode {
+        match self {
+            ObjectAlphaMode::Opaque => AlphaMode::Opaque,
+            ObjectAlphaMode::Mask(cutoff) => AlphaMode::Mask(cutoff),
+            ObjectAlphaMode::Blend => AlphaMode::Blend,
+            ObjectAlphaMode::Premultiplied => AlphaMode::Premultiplied,
+            ObjectAlphaMode::Add => AlphaMode::Add,
+            ObjectAlphaMode::Multiply => AlphaMode::Multiply,
+        }
+    }
+}
+
+/// Surface of an object besides its base color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObjectMaterial {
+    /// From 0 (dielectric) to 1 (metal).
+    pub metallic: f32,
+    /// From 0 (glossy) to 1 (rough).
+    pub perceptual_roughness: f32,
+    /// Color of the light the object emits on its own.
+    pub emissive: Color,
+    /// Multiplier on the emissive color; values above 1 make the object bloom.
+    pub emissive_strength: f32,
+    /// Draws the base color as is, ignoring the lights in the scene.
+    pub unlit: bool,
+    /// `None` blends translucent colors and draws the others opaque.
+    pub alpha_mode: Option<ObjectAlphaMode>,
+}
+
+impl Default for ObjectMaterial {
+    fn default() -> Self {
+        ObjectMaterial {
+            metallic: 0.0,
+            perceptual_roughness: 0.5,
+            emissive: Color::BLACK,
+            emissive_strength: 1.0,
+            unlit: false,
+            alpha_mode: None,
+        }
+    }
+}
+
+impl ObjectMaterial {
+    /// Returns `true` if every factor lies within its range.
+    pub fn is_valid(&self) -> bool {
+        (0.0..=1.0).contains(&self.metallic)
+            && (0.0..=1.0).contains(&self.perceptual_roughness)
+            && self.emissive_strength.is_finite()
+            && self.emissive_strength >= 0.0
+            && self
+                .alpha_mode
+                .is_none_or(|alpha_mode| alpha_mode.is_valid())
+    }
+
+    /// Returns the alpha mode used with the given base color.
+    pub fn resolved_alpha_mode(&self, color: Color) -> ObjectAlphaMode {
+        self.alpha_mode.unwrap_or(if color.alpha() < 1.0 {
+            ObjectAlphaMode::Blend
+        } else {
+            ObjectAlphaMode::Opaque
+        })
+    }
+
+    /// Builds the Bevy material for this surface with the given base color.
+    pub fn standard_material(&self, color: Color) -> StandardMaterial {
+        StandardMaterial {
+            base_color: color,
+            metallic: self.metallic,
+            perceptual_roughness: self.perceptual_roughness,
+            emissive: self.scaled_emissive(),
+            unlit: self.unlit,
+            alpha_mode: self.resolved_alpha_mode(color).alpha_mode(),
+            ..default()
+        }
+    }
+
+    /// Returns the emissive color multiplied by its strength, which may exceed 1.
+    fn scaled_emissive(&self) -> LinearRgba {
+        let emissive = self.emissive.to_linear();
+        (emissive * self.emissive_strength).with_alpha(emissive.alpha)
+    }
+
+    fn key(&self, color: Color) -> MaterialKey {
+        let (alpha_mode, alpha_cutoff) = match self.resolved_alpha_mode(color) {
+            ObjectAlphaMode::Opaque => (0, 0.0),
+            ObjectAlphaMode::Mask(cutoff) => (1, cutoff),
+            ObjectAlphaMode::Blend => (2, 0.0),
+            ObjectAlphaMode::Premultiplied => (3, 0.0),
+            ObjectAlphaMode::Add => (4, 0.0),
+            ObjectAlphaMode::Multiply => (5, 0.0),
+        };
+        MaterialKey {
+            color: color.to_linear().to_f32_array().map(f32::to_bits),
+            emissive: self.scaled_emissive().to_f32_array().map(f32::to_bits),
+            metallic: self.metallic.to_bits(),
+            perceptual_roughness: self.perceptual_roughness.to_bits(),
+            unlit: self.unlit,
+            alpha_mode,
+            alpha_cutoff: alpha_cutoff.to_bits(),
+        }
+    }
+}
+
+/// Identifies the material built for an object, so that objects that look the same can share it.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct MaterialKey {
+    color: [u32; 4],
+    emissive: [u32; 4],
+    metallic: u32,
+    perceptual_roughness: u32,
+    unlit: bool,
+    alpha_mode: u8,
+    alpha_cutoff: u32,
 }
 
 #[derive(Debug, Component, Clone)]
 pub struct ObjectProperties {
     pub color: Color,
-    pub size: f32,
+    pub material: ObjectMaterial,
+    pub size: ObjectSize,
     pub shape: ObjectShape,
+    /// Takes precedence over the size when set; always matches the shape.
+    pub dimensions: Option<ObjectDimensions>,
 }
 
-#[derive(Debug, Component, Clone, Eq, PartialEq)]
+impl ObjectProperties {
+    /// Checks that the mesh for these properties can be built, failing if they refer to a mesh that
+    /// is not registered.
+    pub fn check_mesh(
+        &self,
+        registered_meshes: &RegisteredMeshes,
+    ) -> Result<(), ObjectRequestError> {
+        if self.dimensions.is_none()
+            && let ObjectShape::Mesh(mesh_id) = &self.shape
+        {
+            registered_meshes.get(mesh_id)?;
+        }
+        Ok(())
+    }
+
+    /// Builds the mesh for these properties, failing if they refer to a mesh that is not registered.
+    pub fn mesh(&self, registered_meshes: &RegisteredMeshes) -> Result<Mesh, ObjectRequestError> {
+        match &self.dimensions {
+            Some(dimensions) => Ok(dimensions.mesh()),
+            None => self.shape.mesh(
+                self.size.extents(&self.shape, registered_meshes)?,
+                registered_meshes,
+            ),
+        }
+    }
+
+    fn mesh_key(
+        &self,
+        registered_meshes: &RegisteredMeshes,
+    ) -> Result<MeshKey, ObjectRequestError> {
+        let (from_dimensions, values) = match &self.dimensions {
+            Some(dimensions) => (true, dimensions.values()),
+            None => (
+                false,
+                self.size
+                    .extents(&self.shape, registered_meshes)?
+                    .to_array(),
+            ),
+        };
+        Ok(MeshKey {
+            shape: self.shape.clone(),
+            from_dimensions,
+            values: values.map(f32::to_bits),
+        })
+    }
+}
+
+/// Identifies the mesh built for an object, so that objects with the same geometry can share it.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct MeshKey {
+    shape: ObjectShape,
+    from_dimensions: bool,
+    values: [u32; 3],
+}
+
+#[derive(Debug, Component, Clone, Eq, PartialEq, Hash)]
 pub struct ObjectId {
     pub uuid: Uuid,
 }
@@ -166,3 +1596,193 @@ impl Display for ObjectId {
         write!(f, "ObjectId({})", self.uuid)
     }
 }
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MeshId {
+    pub uuid: Uuid,
+}
+
+impl Display for MeshId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MeshId({})", self.uuid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_world() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<ObjectIndex>();
+        world.init_resource::<ObjectAssetCache>();
+        world.init_resource::<RegisteredMeshes>();
+        world.init_resource::<SimulationClock>();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        world.init_resource::<Events<ObjectRequest>>();
+        world.init_resource::<Events<ObjectChanged>>();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(ObjectRequest::event_handler);
+        (world, schedule)
+    }
+
+    fn spawn_request(object_id: &ObjectId, position: Vec3) -> ObjectRequest {
+        ObjectRequest::Spawn(SpawnObjectRequest {
+            object_id: object_id.clone(),
+            object_properties: ObjectProperties {
+                color: Color::WHITE,
+                size: ObjectSize::Uniform(1.0),
+                shape: ObjectShape::Cube,
+                dimensions: None,
+                material: ObjectMaterial::default(),
+            },
+            position,
+            parent: None,
+            completion: None,
+        })
+    }
+
+    /// Sends the requests as one frame and returns the outcome of each of them.
+    fn run_frame(
+        world: &mut World,
+        schedule: &mut Schedule,
+        requests: Vec<ObjectRequest>,
+    ) -> Vec<Result<(), ObjectRequestError>> {
+        let receivers: Vec<_> = requests
+            .into_iter()
+            .map(|mut request| {
+                let (completion, receiver) = Completion::channel();
+                let slot = match &mut request {
+                    ObjectRequest::SetPosition(request) => &mut request.completion,
+                    ObjectRequest::Spawn(request) => &mut request.completion,
+                    ObjectRequest::Despawn(request) => &mut request.completion,
+                    ObjectRequest::SetTransform(request) => &mut request.completion,
+                    ObjectRequest::SetProperties(request) => &mut request.completion,
+                    ObjectRequest::RegisterMesh(request) => &mut request.completion,
+                    ObjectRequest::SetParent(request) => &mut request.completion,
+                };
+                *slot = Some(completion);
+                world.send_event(request);
+                receiver
+            })
+            .collect();
+
+        schedule.run(world);
+
+        receivers
+            .into_iter()
+            .map(|mut receiver| receiver.try_recv().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn despawn_then_spawn_of_the_same_id_in_one_frame_respawns_it() {
+        let (mut world, mut schedule) = test_world();
+        let object_id = ObjectId {
+            uuid: Uuid::now_v7(),
+        };
+
+        let results = run_frame(
+            &mut world,
+            &mut schedule,
+            vec![spawn_request(&object_id, Vec3::ZERO)],
+        );
+        assert_eq!(results, vec![Ok(())]);
+        let despawned = world.resource::<ObjectIndex>().get(&object_id).unwrap();
+
+        let results = run_frame(
+            &mut world,
+            &mut schedule,
+            vec![
+                ObjectRequest::Despawn(DespawnObjectRequest {
+                    object_id: object_id.clone(),
+                    completion: None,
+                }),
+                spawn_request(&object_id, Vec3::ONE),
+                ObjectRequest::SetPosition(SetObjectPositionRequest {
+                    object_id: object_id.clone(),
+                    position: Vec3::X,
+                    timestamp: None,
+                    completion: None,
+                }),
+            ],
+        );
+        assert_eq!(results, vec![Ok(()), Ok(()), Ok(())]);
+
+        let respawned = world.resource::<ObjectIndex>().get(&object_id).unwrap();
+        assert_ne!(respawned, despawned);
+        assert!(world.get_entity(despawned).is_err());
+        assert_eq!(world.get::<TargetPosition>(respawned).unwrap().0, Vec3::X);
+    }
+
+    #[test]
+    fn parent_requests_see_the_hierarchy_built_earlier_in_the_frame() {
+        let (mut world, mut schedule) = test_world();
+        let parent_id = ObjectId {
+            uuid: Uuid::now_v7(),
+        };
+        let child_id = ObjectId {
+            uuid: Uuid::now_v7(),
+        };
+        let set_parent = |object_id: &ObjectId, parent: &ObjectId| {
+            ObjectRequest::SetParent(SetObjectParentRequest {
+                object_id: object_id.clone(),
+                parent: Some(parent.clone()),
+                keep_world_transform: false,
+                completion: None,
+            })
+        };
+
+        let results = run_frame(
+            &mut world,
+            &mut schedule,
+            vec![
+                spawn_request(&parent_id, Vec3::ZERO),
+                spawn_request(&child_id, Vec3::ZERO),
+                set_parent(&child_id, &parent_id),
+                set_parent(&parent_id, &child_id),
+                ObjectRequest::Despawn(DespawnObjectRequest {
+                    object_id: parent_id.clone(),
+                    completion: None,
+                }),
+                set_parent(&child_id, &parent_id),
+            ],
+        );
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err(ObjectRequestError::ParentCycle(parent_id.clone())),
+                Ok(()),
+                Err(ObjectRequestError::NotFound(child_id)),
+            ]
+        );
+        assert!(world.resource::<ObjectIndex>().is_empty());
+    }
+
+    #[test]
+    fn spawn_after_spawn_of_the_same_id_is_rejected() {
+        let (mut world, mut schedule) = test_world();
+        let object_id = ObjectId {
+            uuid: Uuid::now_v7(),
+        };
+
+        let results = run_frame(
+            &mut world,
+            &mut schedule,
+            vec![
+                spawn_request(&object_id, Vec3::ZERO),
+                spawn_request(&object_id, Vec3::ONE),
+            ],
+        );
+        assert_eq!(
+            results,
+            vec![Ok(()), Err(ObjectRequestError::AlreadyExists(object_id))]
+        );
+        assert_eq!(world.resource::<ObjectIndex>().len(), 1);
+    }
+}
@@ -0,0 +1,361 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::object::{
+    Completion, ObjectId, ObjectIndex, ObjectRequest, ObjectRequestError, complete,
+};
+
+pub struct PrimitiveRequestPlugin;
+
+impl Plugin for PrimitiveRequestPlugin {
+    /// Registers primitive request events, their handler and the systems drawing primitives.
+    ///
+    /// Requests share one event so that they are handled in the order they arrived, e.g. a clear
+    /// followed by new primitives in the same frame leaves only the new ones.
+    /// Primitives are drawn with gizmos after transforms have been propagated, so the ones
+    /// attached to objects follow them as they were drawn this frame.
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PrimitiveIndex>()
+            .add_event::<PrimitiveRequest>()
+            .add_systems(
+                Update,
+                (expire_primitives, PrimitiveRequest::event_handler)
+                    .chain()
+                    .after(super::process_requests)
+                    // Objects spawned in the same frame can be referenced right away.
+                    .after(ObjectRequest::event_handler),
+            )
+            .add_systems(
+                PostUpdate,
+                draw_primitives.after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Requests that draw debug lines, arrows and polylines in the scene.
+#[derive(Debug, Clone, Event)]
+pub enum PrimitiveRequest {
+    SetPrimitive(SetPrimitiveRequest),
+    RemovePrimitive(RemovePrimitiveRequest),
+    ClearPrimitives(ClearPrimitivesRequest),
+}
+
+impl PrimitiveRequest {
+    /// Handles primitive events one by one, in the order they were sent.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        mut primitive_index: ResMut<PrimitiveIndex>,
+        object_index: Res<ObjectIndex>,
+        mut commands: Commands,
+    ) {
+        for event in event_reader.read() {
+            match event {
+                PrimitiveRequest::SetPrimitive(request) => {
+                    request.apply(&mut primitive_index, &object_index, &mut commands);
+                }
+                PrimitiveRequest::RemovePrimitive(request) => {
+                    request.apply(&mut primitive_index, &mut commands);
+                }
+                PrimitiveRequest::ClearPrimitives(request) => {
+                    request.apply(&mut primitive_index, &mut commands);
+                }
+            }
+        }
+    }
+}
+
+/// Event writer for primitive requests.
+#[derive(SystemParam)]
+pub struct PrimitiveRequestWriters<'w> {
+    requests: EventWriter<'w, PrimitiveRequest>,
+}
+
+impl PrimitiveRequestWriters<'_> {
+    /// Emits the given primitive request as an event.
+    pub fn write(&mut self, request: PrimitiveRequest) {
+        self.requests.write(request);
+    }
+}
+
+/// Resource mapping primitive IDs to their entities.
+#[derive(Resource, Debug, Default)]
+pub struct PrimitiveIndex {
+    entities: HashMap<PrimitiveId, Entity>,
+}
+
+impl PrimitiveIndex {
+    /// Returns the entity of the primitive with the given ID, if there is one.
+    pub fn get(&self, primitive_id: &PrimitiveId) -> Option<Entity> {
+        self.entities.get(primitive_id).copied()
+    }
+
+    /// Returns the number of primitives in the scene.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if there are no primitives in the scene.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// A point a primitive is drawn through.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrimitivePoint {
+    /// A fixed position in the world.
+    Position(Vec3),
+    /// A point following an object, offset from its origin in world units; the primitive is
+    /// removed along with the object.
+    Object { object_id: ObjectId, offset: Vec3 },
+}
+
+impl PrimitivePoint {
+    /// Returns the world position of the point, or `None` if its object no longer exists.
+    fn resolve(
+        &self,
+        object_index: &ObjectIndex,
+        transforms: &Query<&GlobalTransform>,
+    ) -> Option<Vec3> {
+        match self {
+            PrimitivePoint::Position(position) => Some(*position),
+            PrimitivePoint::Object { object_id, offset } => object_index
+                .get(object_id)
+                .and_then(|entity| transforms.get(entity).ok())
+                .map(|transform| transform.translation() + *offset),
+        }
+    }
+
+    /// Returns the object the point follows, if any.
+    fn object_id(&self) -> Option<&ObjectId> {
+        match self {
+            PrimitivePoint::Position(_) => None,
+            PrimitivePoint::Object { object_id, .. } => Some(object_id),
+        }
+    }
+}
+
+/// The shape of a primitive.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrimitiveShape {
+    /// A line segment.
+    Line {
+        start: PrimitivePoint,
+        end: PrimitivePoint,
+    },
+    /// A line segment with an arrow head at its end, e.g. for velocity or force vectors.
+    Arrow {
+        start: PrimitivePoint,
+        end: PrimitivePoint,
+    },
+    /// Line segments joining consecutive points.
+    Polyline(Vec<PrimitivePoint>),
+}
+
+impl PrimitiveShape {
+    /// Returns the objects the shape follows.
+    fn object_ids(&self) -> impl Iterator<Item = &ObjectId> {
+        let points = match self {
+            PrimitiveShape::Line { start, end } | PrimitiveShape::Arrow { start, end } => {
+                vec![start, end]
+            }
+            PrimitiveShape::Polyline(points) => points.iter().collect(),
+        };
+        points.into_iter().filter_map(PrimitivePoint::object_id)
+    }
+}
+
+/// Component of a primitive drawn with gizmos every frame.
+#[derive(Component, Debug, Clone)]
+pub struct DebugPrimitive {
+    pub primitive_id: PrimitiveId,
+    pub shape: PrimitiveShape,
+    pub color: Color,
+}
+
+/// Component of a primitive that is removed once the timer finishes.
+#[derive(Component, Debug)]
+struct PrimitiveLifetime(Timer);
+
+#[derive(Debug, Clone)]
+pub struct SetPrimitiveRequest {
+    pub primitive_id: PrimitiveId,
+    pub shape: PrimitiveShape,
+    pub color: Color,
+    /// How long the primitive is drawn for; `None` keeps it until it is removed.
+    pub lifetime: Option<Duration>,
+    pub completion: Option<Completion>,
+}
+
+impl SetPrimitiveRequest {
+    /// Creates the primitive, or replaces the primitive with the same ID.
+    fn apply(
+        &self,
+        primitive_index: &mut PrimitiveIndex,
+        object_index: &ObjectIndex,
+        commands: &mut Commands,
+    ) {
+        if let Some(object_id) = self
+            .shape
+            .object_ids()
+            .find(|object_id| !object_index.contains(object_id))
+        {
+            trace!("Object {} not found; ignoring primitive", object_id);
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::NotFound(object_id.clone())),
+            );
+            return;
+        }
+
+        trace!(
+            "Setting primitive {} to {:?} for {:?}",
+            self.primitive_id, self.shape, self.lifetime
+        );
+
+        let primitive = DebugPrimitive {
+            primitive_id: self.primitive_id.clone(),
+            shape: self.shape.clone(),
+            color: self.color,
+        };
+        let entity = match primitive_index.get(&self.primitive_id) {
+            Some(entity) => {
+                commands.entity(entity).insert(primitive);
+                entity
+            }
+            None => {
+                let entity = commands
+                    .spawn((Name::new(self.primitive_id.to_string()), primitive))
+                    .id();
+                primitive_index
+                    .entities
+                    .insert(self.primitive_id.clone(), entity);
+                entity
+            }
+        };
+        match self.lifetime {
+            Some(lifetime) => {
+                commands
+                    .entity(entity)
+                    .insert(PrimitiveLifetime(Timer::new(lifetime, TimerMode::Once)));
+            }
+            None => {
+                commands.entity(entity).remove::<PrimitiveLifetime>();
+            }
+        }
+
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemovePrimitiveRequest {
+    pub primitive_id: PrimitiveId,
+    pub completion: Option<Completion>,
+}
+
+impl RemovePrimitiveRequest {
+    /// Despawns the matching primitive.
+    fn apply(&self, primitive_index: &mut PrimitiveIndex, commands: &mut Commands) {
+        let Some(entity) = primitive_index.entities.remove(&self.primitive_id) else {
+            trace!(
+                "Primitive {} not found; ignoring removal",
+                self.primitive_id
+            );
+            complete(
+                &self.completion,
+                Err(ObjectRequestError::PrimitiveNotFound(
+                    self.primitive_id.clone(),
+                )),
+            );
+            return;
+        };
+
+        trace!("Removing primitive {}", self.primitive_id);
+        commands.entity(entity).despawn();
+        complete(&self.completion, Ok(()));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClearPrimitivesRequest {
+    pub completion: Option<Completion>,
+}
+
+impl ClearPrimitivesRequest {
+    /// Despawns every primitive.
+    fn apply(&self, primitive_index: &mut PrimitiveIndex, commands: &mut Commands) {
+        trace!("Clearing {} primitives", primitive_index.len());
+        for (_, entity) in primitive_index.entities.drain() {
+            commands.entity(entity).despawn();
+        }
+        complete(&self.completion, Ok(()));
+    }
+}
+
+/// Removes primitives whose lifetime has run out or whose objects have been despawned.
+fn expire_primitives(
+    time: Res<Time>,
+    mut primitives: Query<(Entity, &DebugPrimitive, Option<&mut PrimitiveLifetime>)>,
+    mut primitive_index: ResMut<PrimitiveIndex>,
+    object_index: Res<ObjectIndex>,
+    mut commands: Commands,
+) {
+    for (entity, primitive, lifetime) in &mut primitives {
+        let expired = lifetime.is_some_and(|mut lifetime| lifetime.0.tick(time.delta()).finished());
+        let orphaned = primitive
+            .shape
+            .object_ids()
+            .any(|object_id| !object_index.contains(object_id));
+
+        if expired || orphaned {
+            trace!("Primitive {} expired", primitive.primitive_id);
+            primitive_index.entities.remove(&primitive.primitive_id);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Draws every primitive with gizmos at the current position of its points.
+fn draw_primitives(
+    mut gizmos: Gizmos,
+    primitives: Query<&DebugPrimitive>,
+    object_index: Res<ObjectIndex>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for primitive in &primitives {
+        let resolve = |point: &PrimitivePoint| point.resolve(&object_index, &transforms);
+
+        match &primitive.shape {
+            PrimitiveShape::Line { start, end } => {
+                if let (Some(start), Some(end)) = (resolve(start), resolve(end)) {
+                    gizmos.line(start, end, primitive.color);
+                }
+            }
+            PrimitiveShape::Arrow { start, end } => {
+                if let (Some(start), Some(end)) = (resolve(start), resolve(end)) {
+                    gizmos.arrow(start, end, primitive.color);
+                }
+            }
+            PrimitiveShape::Polyline(points) => {
+                gizmos.linestrip(points.iter().filter_map(resolve), primitive.color);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PrimitiveId {
+    pub uuid: Uuid,
+}
+
+impl Display for PrimitiveId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PrimitiveId({})", self.uuid)
+    }
+}
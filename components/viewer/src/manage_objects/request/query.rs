@@ -0,0 +1,224 @@
+use bevy::diagnostic::FrameCount;
+use bevy::ecs::query::QueryItem;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use super::object::{
+    ObjectId, ObjectIndex, ObjectProperties, TargetPosition, TargetRotation, TargetScale,
+};
+use crate::types::ResponseSender;
+
+pub struct QueryRequestPlugin;
+
+impl Plugin for QueryRequestPlugin {
+    /// Registers query request events and the systems answering them.
+    ///
+    /// Queries are answered in `PostUpdate`, after every object request of the frame has been
+    /// applied, so a snapshot of frame `n` reflects exactly the changes broadcast up to frame `n`.
+    fn build(&self, app: &mut App) {
+        app.add_event::<GetObjectRequest>()
+            .add_systems(PostUpdate, GetObjectRequest::event_handler)
+            .add_event::<ListObjectsRequest>()
+            .add_systems(PostUpdate, ListObjectsRequest::event_handler)
+            .add_event::<GetSceneSnapshotRequest>()
+            .add_systems(PostUpdate, GetSceneSnapshotRequest::event_handler);
+    }
+}
+
+/// Requests that read the state of the scene and reply through a [`ResponseSender`].
+#[derive(Debug)]
+pub enum QueryRequest {
+    GetObject(GetObjectRequest),
+    ListObjects(ListObjectsRequest),
+    GetSceneSnapshot(GetSceneSnapshotRequest),
+}
+
+/// Event writers for every kind of query request.
+#[derive(SystemParam)]
+pub struct QueryRequestWriters<'w> {
+    get_object: EventWriter<'w, GetObjectRequest>,
+    list_objects: EventWriter<'w, ListObjectsRequest>,
+    get_scene_snapshot: EventWriter<'w, GetSceneSnapshotRequest>,
+}
+
+impl QueryRequestWriters<'_> {
+    /// Emits the event corresponding to the given query request.
+    pub fn write(&mut self, request: QueryRequest) {
+        match request {
+            QueryRequest::GetObject(get_object_request) => {
+                self.get_object.write(get_object_request);
+            }
+            QueryRequest::ListObjects(list_objects_request) => {
+                self.list_objects.write(list_objects_request);
+            }
+            QueryRequest::GetSceneSnapshot(get_scene_snapshot_request) => {
+                self.get_scene_snapshot.write(get_scene_snapshot_request);
+            }
+        }
+    }
+}
+
+/// The state of a single object as seen by the viewer.
+#[derive(Debug, Clone)]
+pub struct ObjectSnapshot {
+    pub object_id: ObjectId,
+    pub object_properties: ObjectProperties,
+    /// The transform currently rendered.
+    pub transform: Transform,
+    /// The transform the object is interpolating towards.
+    pub target_transform: Transform,
+    /// The object this one is attached to; both transforms are relative to it.
+    pub parent: Option<ObjectId>,
+}
+
+/// Query data needed to build an [`ObjectSnapshot`].
+type ObjectSnapshotData = (
+    &'static ObjectId,
+    &'static ObjectProperties,
+    &'static Transform,
+    &'static TargetPosition,
+    &'static TargetRotation,
+    &'static TargetScale,
+    Option<&'static ChildOf>,
+);
+
+impl ObjectSnapshot {
+    /// Builds a snapshot from the components of an object entity, looking up the ID of its parent.
+    fn from_query_item(
+        (object_id, object_properties, transform, target_pos, target_rot, target_scale, child_of): QueryItem<
+            ObjectSnapshotData,
+        >,
+        object_ids: &Query<&ObjectId>,
+    ) -> Self {
+        ObjectSnapshot {
+            object_id: object_id.clone(),
+            object_properties: object_properties.clone(),
+            transform: *transform,
+            target_transform: Transform {
+                translation: target_pos.0,
+                rotation: target_rot.0,
+                scale: target_scale.0,
+            },
+            parent: child_of.and_then(|child_of| object_ids.get(child_of.parent()).ok().cloned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct GetObjectRequest {
+    pub object_id: ObjectId,
+    /// Receives `None` if no object has the requested ID.
+    pub response: ResponseSender<Option<ObjectSnapshot>>,
+}
+
+impl GetObjectRequest {
+    /// Replies with the snapshot of the requested object, if it exists.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        object_index: Res<ObjectIndex>,
+        query: Query<ObjectSnapshotData>,
+        object_ids: Query<&ObjectId>,
+    ) {
+        for event in event_reader.read() {
+            let snapshot = object_index
+                .get(&event.object_id)
+                .and_then(|entity| query.get(entity).ok())
+                .map(|item| ObjectSnapshot::from_query_item(item, &object_ids));
+
+            trace!("Replying to get object {}: {:?}", event.object_id, snapshot);
+            event.response.send(snapshot);
+        }
+    }
+}
+
+/// A page of objects ordered by ID.
+#[derive(Debug, Clone)]
+pub struct ObjectPage {
+    pub objects: Vec<ObjectSnapshot>,
+    /// The ID to pass as `start_after` to get the next page, if there is one.
+    pub next_start_after: Option<ObjectId>,
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct ListObjectsRequest {
+    /// Maximum number of objects in the page.
+    pub page_size: usize,
+    /// Only objects with an ID greater than this one are listed.
+    pub start_after: Option<ObjectId>,
+    pub response: ResponseSender<ObjectPage>,
+}
+
+impl ListObjectsRequest {
+    /// Replies with one page of objects ordered by ID.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        query: Query<ObjectSnapshotData>,
+        object_ids: Query<&ObjectId>,
+    ) {
+        for event in event_reader.read() {
+            let mut items: Vec<_> = query
+                .iter()
+                .filter(|(object_id, ..)| {
+                    event
+                        .start_after
+                        .as_ref()
+                        .is_none_or(|start_after| object_id.uuid > start_after.uuid)
+                })
+                .collect();
+            items.sort_unstable_by_key(|(object_id, ..)| object_id.uuid);
+
+            let has_more = items.len() > event.page_size;
+            let objects: Vec<_> = items
+                .into_iter()
+                .take(event.page_size)
+                .map(|item| ObjectSnapshot::from_query_item(item, &object_ids))
+                .collect();
+            let next_start_after = if has_more {
+                objects.last().map(|snapshot| snapshot.object_id.clone())
+            } else {
+                None
+            };
+
+            trace!("Replying to list objects with {} objects", objects.len());
+            event.response.send(ObjectPage {
+                objects,
+                next_start_after,
+            });
+        }
+    }
+}
+
+/// The state of every object in the scene within a single frame.
+#[derive(Debug, Clone)]
+pub struct SceneSnapshot {
+    pub objects: Vec<ObjectSnapshot>,
+    pub frame: u32,
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct GetSceneSnapshotRequest {
+    pub response: ResponseSender<SceneSnapshot>,
+}
+
+impl GetSceneSnapshotRequest {
+    /// Replies with the state of every object in the scene.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        frame_count: Res<FrameCount>,
+        query: Query<ObjectSnapshotData>,
+        object_ids: Query<&ObjectId>,
+    ) {
+        for event in event_reader.read() {
+            let objects: Vec<_> = query
+                .iter()
+                .map(|item| ObjectSnapshot::from_query_item(item, &object_ids))
+                .collect();
+
+            trace!("Replying to scene snapshot with {} objects", objects.len());
+            event.response.send(SceneSnapshot {
+                objects,
+                frame: frame_count.0,
+            });
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use super::object::{
+    Completion, ObjectId, ObjectIndex, ObjectRequest, ObjectRequestError, complete,
+};
+
+/// Distance an object must move before a new point is added to its trail.
+const MIN_SAMPLE_DISTANCE: f32 = 0.01;
+
+pub struct TrailRequestPlugin;
+
+impl Plugin for TrailRequestPlugin {
+    /// Registers trail request events, their handler and the system recording trails.
+    ///
+    /// Trails are recorded after transforms have been propagated, so they follow objects in world
+    /// space, including the ones attached to a parent.
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrailIndex>()
+            .add_event::<SetObjectTrailRequest>()
+            .add_systems(
+                Update,
+                SetObjectTrailRequest::event_handler
+                    .after(super::process_requests)
+                    // Objects spawned in the same frame can get a trail right away.
+                    .after(ObjectRequest::event_handler),
+            )
+            .add_systems(
+                PostUpdate,
+                update_trails.after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Requests that draw the recent motion of objects.
+#[derive(Debug)]
+pub enum TrailRequest {
+    SetObjectTrail(SetObjectTrailRequest),
+}
+
+/// Event writers for every kind of trail request.
+#[derive(SystemParam)]
+pub struct TrailRequestWriters<'w> {
+    set_object_trail: EventWriter<'w, SetObjectTrailRequest>,
+}
+
+impl TrailRequestWriters<'_> {
+    /// Emits the event corresponding to the given trail request.
+    pub fn write(&mut self, request: TrailRequest) {
+        match request {
+            TrailRequest::SetObjectTrail(set_object_trail_request) => {
+                self.set_object_trail.write(set_object_trail_request);
+            }
+        }
+    }
+}
+
+/// How the trail of an object is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrailSettings {
+    /// How long a position stays on the trail; it fades out over that time.
+    pub length: Duration,
+    /// In logical pixels.
+    pub width: f32,
+    pub color: Color,
+}
+
+impl TrailSettings {
+    /// Returns `true` if the length is non-zero and the width is finite and positive.
+    pub fn is_valid(&self) -> bool {
+        !self.length.is_zero() && self.width.is_finite() && self.width > 0.0
+    }
+
+    /// Returns the color of a point on the trail recorded `age` ago.
+    fn faded_color(&self, age: Duration) -> Color {
+        let remaining = 1.0 - age.as_secs_f32() / self.length.as_secs_f32();
+        self.color
+            .with_alpha(self.color.alpha() * remaining.clamp(0.0, 1.0))
+    }
+}
+
+/// Component of the entity drawing the trail of an object.
+#[derive(Component, Debug)]
+pub struct Trail {
+    pub object: Entity,
+    pub settings: TrailSettings,
+    /// World positions of the object, oldest first, with the time they were recorded at.
+    samples: VecDeque<(Vec3, Duration)>,
+}
+
+impl Trail {
+    /// Returns the components of a new, empty trail of an object.
+    fn bundle(
+        object: Entity,
+        settings: &TrailSettings,
+        gizmo_assets: &mut Assets<GizmoAsset>,
+    ) -> (Self, Gizmo) {
+        (
+            Trail {
+                object,
+                settings: settings.clone(),
+                samples: VecDeque::new(),
+            },
+            Gizmo {
+                handle: gizmo_assets.add(GizmoAsset::new()),
+                line_config: GizmoLineConfig {
+                    width: settings.width,
+                    ..default()
+                },
+                ..default()
+            },
+        )
+    }
+}
+
+/// Resource mapping object entities to the entities of their trails.
+///
+/// Updated as soon as a trail is started or removed, so that several requests for the same object
+/// in one frame never draw more than one trail.
+#[derive(Resource, Debug, Default)]
+pub struct TrailIndex {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl TrailIndex {
+    /// Returns the trail entity of the given object, if it has a trail.
+    pub fn get(&self, object: Entity) -> Option<Entity> {
+        self.entities.get(&object).copied()
+    }
+
+    /// Returns the number of trails in the scene.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if there are no trails in the scene.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct SetObjectTrailRequest {
+    pub object_id: ObjectId,
+    /// The new trail settings; `None` removes the trail.
+    pub trail: Option<TrailSettings>,
+    pub completion: Option<Completion>,
+}
+
+impl SetObjectTrailRequest {
+    /// Handles trail events by starting, restyling or removing the trail of objects.
+    ///
+    /// Restyling a trail keeps the positions recorded so far.
+    pub fn event_handler(
+        mut event_reader: EventReader<Self>,
+        object_index: Res<ObjectIndex>,
+        mut trail_index: ResMut<TrailIndex>,
+        mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
+        mut commands: Commands,
+        mut trails: Query<(&mut Trail, &mut Gizmo)>,
+    ) {
+        for event in event_reader.read() {
+            let Some(entity) = object_index.get(&event.object_id) else {
+                trace!("Object {} not found; ignoring trail", event.object_id);
+                complete(
+                    &event.completion,
+                    Err(ObjectRequestError::NotFound(event.object_id.clone())),
+                );
+                continue;
+            };
+
+            trace!(
+                "Setting trail of object {} to {:?}",
+                event.object_id, event.trail
+            );
+
+            match (&event.trail, trail_index.get(entity)) {
+                (Some(settings), Some(trail_entity)) => match trails.get_mut(trail_entity) {
+                    Ok((mut trail, mut gizmo)) => {
+                        trail.settings = settings.clone();
+                        gizmo.line_config.width = settings.width;
+                    }
+                    // Started earlier in this frame, so its components are not there yet.
+                    Err(_) => {
+                        commands.entity(trail_entity).insert(Trail::bundle(
+                            entity,
+                            settings,
+                            &mut gizmo_assets,
+                        ));
+                    }
+                },
+                (Some(settings), None) => {
+                    let trail_entity = commands
+                        .spawn((
+                            Name::new(format!("Trail of {}", event.object_id)),
+                            Trail::bundle(entity, settings, &mut gizmo_assets),
+                        ))
+                        .id();
+                    trail_index.entities.insert(entity, trail_entity);
+                }
+                (None, Some(trail_entity)) => {
+                    commands.entity(trail_entity).despawn();
+                    trail_index.entities.remove(&entity);
+                }
+                (None, None) => {}
+            }
+
+            complete(&event.completion, Ok(()));
+        }
+    }
+}
+
+/// Records the current world position of every object with a trail, forgets positions older than
+/// the trail length and redraws the trail fading from the object to its oldest position.
+///
+/// The trails of despawned objects are removed.
+fn update_trails(
+    time: Res<Time>,
+    mut trails: Query<(Entity, &mut Trail, &Gizmo)>,
+    transforms: Query<&GlobalTransform>,
+    mut trail_index: ResMut<TrailIndex>,
+    mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed();
+
+    for (entity, mut trail, gizmo) in &mut trails {
+        let Ok(transform) = transforms.get(trail.object) else {
+            trail_index.entities.remove(&trail.object);
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let position = transform.translation();
+        let length = trail.settings.length;
+
+        while trail
+            .samples
+            .front()
+            .is_some_and(|&(_, recorded_at)| now.saturating_sub(recorded_at) > length)
+        {
+            trail.samples.pop_front();
+        }
+        if trail
+            .samples
+            .back()
+            .is_none_or(|&(last, _)| last.distance(position) > MIN_SAMPLE_DISTANCE)
+        {
+            trail.samples.push_back((position, now));
+        }
+
+        let Some(gizmo_asset) = gizmo_assets.get_mut(&gizmo.handle) else {
+            continue;
+        };
+        gizmo_asset.clear();
+        gizmo_asset.linestrip_gradient(
+            trail
+                .samples
+                .iter()
+                .map(|&(sample, recorded_at)| {
+                    (sample, trail.settings.faded_color(now - recorded_at))
+                })
+                // The trail always reaches the object, even if it moved too little to be recorded.
+                .chain([(position, trail.settings.color)]),
+        );
+    }
+}
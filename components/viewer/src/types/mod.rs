@@ -1,43 +1,142 @@
-use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::sync::{Arc, Mutex};
 
-#[derive(Clone, Debug)]
-pub struct ThreadSafeVecRw<T> {
-    inner: Arc<RwLock<Vec<T>>>,
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+/// A bounded multi-producer, single-consumer queue that can be shared between threads.
+#[derive(Debug)]
+pub struct BoundedQueue<T> {
+    sender: mpsc::Sender<T>,
+    receiver: Mutex<mpsc::Receiver<T>>,
 }
 
-impl<T> ThreadSafeVecRw<T> {
-    /// Creates an empty, thread-safe vector.
-    pub fn new() -> Self {
-        ThreadSafeVecRw {
-            inner: Arc::new(RwLock::new(Vec::new())),
+#[derive(Error, Debug)]
+#[error("Queue is full")]
+pub struct QueueFullError<T>(pub T);
+
+impl<T> BoundedQueue<T> {
+    /// Creates an empty queue holding at most `capacity` values.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity);
+        BoundedQueue {
+            sender,
+            receiver: Mutex::new(receiver),
         }
     }
 
-    /// Appends a value to the inner vector with write-lock protection.
-    pub fn push(&self, value: T) {
-        let mut vec = self.inner.write().unwrap();
-        vec.push(value);
+    /// Appends a value without waiting, handing it back if the queue is full.
+    pub fn try_push(&self, value: T) -> Result<(), QueueFullError<T>> {
+        self.sender.try_send(value).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(value) | mpsc::error::TrySendError::Closed(value) => {
+                QueueFullError(value)
+            }
+        })
+    }
+
+    /// Appends every value without waiting, or none of them if the queue cannot hold them all.
+    pub fn try_push_all(&self, values: Vec<T>) -> Result<(), QueueFullError<Vec<T>>> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        let Ok(permits) = self.sender.try_reserve_many(values.len()) else {
+            return Err(QueueFullError(values));
+        };
+        for (permit, value) in permits.zip(values) {
+            permit.send(value);
+        }
+        Ok(())
     }
 
-    /// Retrieves and clones the element at the given index, if any.
-    pub fn get(&self, index: usize) -> Option<T>
-    where
-        T: Clone,
-    {
-        let vec = self.inner.read().unwrap();
-        vec.get(index).cloned()
+    /// Appends a value, waiting until the queue has room for it.
+    pub async fn push(&self, value: T) {
+        // The receiver lives as long as the queue, so the channel cannot be closed.
+        let _ = self.sender.send(value).await;
     }
 
-    /// Returns the current number of elements in the vector.
+    /// Removes and returns up to `max` of the values currently in the queue, oldest first.
+    pub fn drain(&self, max: usize) -> Vec<T> {
+        let mut receiver = self.receiver.lock().unwrap();
+        let mut values = Vec::with_capacity(receiver.len().min(max));
+        while values.len() < max {
+            match receiver.try_recv() {
+                Ok(value) => values.push(value),
+                Err(_) => break,
+            }
+        }
+        values
+    }
+
+    /// Returns the number of values currently in the queue.
     pub fn len(&self) -> usize {
-        let vec = self.inner.read().unwrap();
-        vec.len()
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Returns `true` if the queue holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A cloneable one-shot sender used to reply to a request from inside the Bevy world.
+///
+/// Only the first call to [`ResponseSender::send`] delivers a value; later calls are ignored.
+#[derive(Debug)]
+pub struct ResponseSender<T> {
+    inner: Arc<Mutex<Option<oneshot::Sender<T>>>>,
+}
+
+impl<T> ResponseSender<T> {
+    /// Creates a new sender together with the receiver awaiting its response.
+    pub fn channel() -> (Self, oneshot::Receiver<T>) {
+        let (sender, receiver) = oneshot::channel();
+        (
+            ResponseSender {
+                inner: Arc::new(Mutex::new(Some(sender))),
+            },
+            receiver,
+        )
+    }
+
+    /// Sends the response if it has not been sent yet and the receiver is still waiting.
+    pub fn send(&self, value: T) {
+        let sender = self.inner.lock().unwrap().take();
+        if let Some(sender) = sender {
+            // The receiver may have been dropped, e.g. when the client cancelled the request.
+            let _ = sender.send(value);
+        }
+    }
+}
+
+impl<T> Clone for ResponseSender<T> {
+    fn clone(&self) -> Self {
+        ResponseSender {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_push_all_queues_everything_or_nothing() {
+        let queue = BoundedQueue::new(3);
+        queue.try_push(0).unwrap();
+
+        let rejected = queue.try_push_all(vec![1, 2, 3]).unwrap_err();
+        assert_eq!(rejected.0, vec![1, 2, 3]);
+        assert_eq!(queue.len(), 1);
+
+        queue.try_push_all(vec![1, 2]).unwrap();
+        assert_eq!(queue.drain(usize::MAX), vec![0, 1, 2]);
     }
 
-    /// Returns a read guard for the internal vector or an error if poisoned.
-    pub fn get_reader(
-        &self,
-    ) -> Result<RwLockReadGuard<Vec<T>>, std::sync::PoisonError<RwLockReadGuard<Vec<T>>>> {
-        Ok(self.inner.read()?)
+    #[test]
+    fn try_push_all_accepts_an_empty_sequence() {
+        let queue = BoundedQueue::<u32>::new(1);
+        queue.try_push(0).unwrap();
+        queue.try_push_all(Vec::new()).unwrap();
+        assert_eq!(queue.len(), 1);
     }
 }